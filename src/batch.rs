@@ -0,0 +1,475 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::AddAssign;
+
+use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+
+use crate::run::{run_bootloader, RunOptions};
+use crate::tasks::BootloaderTaskError;
+use crate::{CairoPieBytes, CairoPieTask, Error, TaskSpec};
+
+/// Small example PIEs run by `BatchPlanner::measure`.
+const CALIBRATION_PIES: [&[u8]; 4] = [
+    include_bytes!("../examples/noop.zip"),
+    include_bytes!("../examples/fibonacci_with_output.zip"),
+    include_bytes!("../examples/bitwise.zip"),
+    include_bytes!("../examples/example.zip"),
+];
+
+#[derive(thiserror::Error, Debug)]
+pub enum BatchPlanError {
+    #[error("Failed to load task {0}: {1}")]
+    Task(usize, std::io::Error),
+
+    #[error("Failed to run task {0} to estimate its cost: {1}")]
+    Run(usize, Box<Error>),
+
+    #[error("Task {task_index} does not fit in a single run, it needs about {estimated}")]
+    TaskTooLarge {
+        task_index: usize,
+        estimated: RunCost,
+    },
+}
+
+/// Steps and builtin instances of a bootloader run, or of the part of a run spent on a task.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunCost {
+    pub n_steps: usize,
+    pub builtin_instances: HashMap<BuiltinName, usize>,
+}
+
+impl RunCost {
+    fn map(&self, f: impl Fn(usize) -> usize) -> Self {
+        Self {
+            n_steps: f(self.n_steps),
+            builtin_instances: self
+                .builtin_instances
+                .iter()
+                .map(|(builtin, instances)| (*builtin, f(*instances)))
+                .collect(),
+        }
+    }
+
+    fn scaled(&self, factor: usize) -> Self {
+        self.map(|cost| cost * factor)
+    }
+
+    /// Applies `f` to each pair of components of `self` and `other`, missing builtins count as 0.
+    fn zip_with(&self, other: &Self, f: impl Fn(usize, usize) -> usize) -> Self {
+        let mut builtin_instances = self.builtin_instances.clone();
+        for builtin in other.builtin_instances.keys() {
+            builtin_instances.entry(*builtin).or_insert(0);
+        }
+        for (builtin, instances) in builtin_instances.iter_mut() {
+            let other_instances = other.builtin_instances.get(builtin).copied().unwrap_or(0);
+            *instances = f(*instances, other_instances);
+        }
+        builtin_instances.retain(|_, instances| *instances != 0);
+        Self {
+            n_steps: f(self.n_steps, other.n_steps),
+            builtin_instances,
+        }
+    }
+
+    fn saturating_sub(&self, other: &Self) -> Self {
+        self.zip_with(other, usize::saturating_sub)
+    }
+
+    fn max(&self, other: &Self) -> Self {
+        self.zip_with(other, usize::max)
+    }
+}
+
+impl From<&ExecutionResources> for RunCost {
+    fn from(resources: &ExecutionResources) -> Self {
+        Self {
+            n_steps: resources.n_steps,
+            builtin_instances: resources.builtin_instance_counter.clone(),
+        }
+    }
+}
+
+impl AddAssign<&RunCost> for RunCost {
+    fn add_assign(&mut self, other: &RunCost) {
+        *self = self.zip_with(other, |a, b| a + b);
+    }
+}
+
+impl fmt::Display for RunCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} steps", self.n_steps)?;
+        let mut builtin_instances: Vec<_> = self
+            .builtin_instances
+            .iter()
+            .map(|(builtin, instances)| (builtin.to_str(), instances))
+            .collect();
+        builtin_instances.sort();
+        for (builtin, instances) in builtin_instances {
+            write!(f, ", {instances} {builtin} instances")?;
+        }
+        Ok(())
+    }
+}
+
+/// Size limits of a single bootloader run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchBudget {
+    /// Maximum number of steps.
+    pub max_steps: Option<usize>,
+    /// Maximum log2 of the size of each trace component once padded by stwo to the next power of
+    /// two: the main trace has one row per step, each builtin component one row per instance.
+    pub max_trace_log_size: Option<u32>,
+}
+
+impl BatchBudget {
+    fn allows(&self, cost: &RunCost) -> bool {
+        let max_component_size = self.max_trace_log_size.map(|log_size| 1usize << log_size);
+        let fits = |size: usize, max_size: Option<usize>| max_size.map_or(true, |max| size <= max);
+
+        fits(cost.n_steps, self.max_steps)
+            && fits(cost.n_steps, max_component_size)
+            && cost
+                .builtin_instances
+                .values()
+                .all(|instances| fits(*instances, max_component_size))
+    }
+}
+
+/// A group of tasks executed by a single bootloader run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchRun {
+    /// Indices of the tasks in the original task list, in order.
+    pub task_indices: Vec<usize>,
+    /// Estimated cost of the bootloader run, overheads included.
+    pub estimated: RunCost,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchPlan {
+    pub runs: Vec<BatchRun>,
+}
+
+/// Splits a list of tasks into several bootloader runs that each fit in a budget.
+///
+/// The cost of a Cairo PIE task is estimated from the execution resources recorded in its PIE,
+/// plus the work of the bootloader itself: a fixed cost per task and the cost of hashing each word
+/// of its program. `measure` derives these overheads from runs of the bundled bootloader. Other
+/// tasks record no resources: they are run alone with the bundled bootloader.
+#[derive(Debug, Clone)]
+pub struct BatchPlanner {
+    pub budget: BatchBudget,
+    /// Options of the bootloader runs used to measure the costs.
+    pub run_options: RunOptions,
+    /// Cost of the bootloader regardless of its tasks.
+    pub run_overhead: RunCost,
+    /// Cost added by the bootloader for each task (loading it, checking its builtins, writing its
+    /// output), besides the hash of its program.
+    pub task_overhead: RunCost,
+    /// Cost of hashing one word of a task program.
+    pub program_hash_cost_per_word: RunCost,
+}
+
+impl BatchPlanner {
+    /// Measures the overheads of the bundled bootloader, by running it without tasks and then
+    /// on each of a few small example PIEs, hashed with Pedersen and with Poseidon.
+    ///
+    /// The overheads are rounded up so that the estimates of the example PIEs are never below
+    /// their actual cost.
+    ///
+    /// The bootloader runs with the layout, proof mode and output options of the planned runs,
+    /// which change its cost. Options that only make sense for the planned tasks (packed outputs,
+    /// fact topologies file) are ignored.
+    pub fn measure(budget: BatchBudget, options: &RunOptions) -> Result<Self, Error> {
+        let run_options = RunOptions {
+            packed_outputs: None,
+            fact_topologies_path: None,
+            ..options.clone()
+        };
+        let run_cost = |tasks: Vec<TaskSpec>| -> Result<RunCost, Error> {
+            let run = run_bootloader(tasks, &run_options)?;
+            Ok(RunCost::from(&run.resources))
+        };
+        let run_overhead = run_cost(vec![])?;
+
+        let mut task_overhead = RunCost::default();
+        let mut program_hash_cost_per_word = RunCost::default();
+        for use_poseidon in [false, true] {
+            // Bootloader cost of each task besides its own execution, with its program size.
+            let mut samples = Vec::with_capacity(CALIBRATION_PIES.len());
+            for pie in CALIBRATION_PIES {
+                let task = TaskSpec::CairoPieBytes(CairoPieBytes::new(pie.to_vec(), use_poseidon));
+                let task_cost = run_cost(vec![task.clone()])?.saturating_sub(&run_overhead);
+                let task = task
+                    .load_task()
+                    .map_err(|e| Error::from(BootloaderTaskError::from(e)))?;
+                let Some(task) = task.as_any().downcast_ref::<CairoPieTask>() else {
+                    continue;
+                };
+                let pie_cost = RunCost::from(&task.cairo_pie.execution_resources);
+                samples.push((program_size(task), task_cost.saturating_sub(&pie_cost)));
+            }
+
+            let (Some(smallest), Some(largest)) = (
+                samples.iter().min_by_key(|(size, _)| *size),
+                samples.iter().max_by_key(|(size, _)| *size),
+            ) else {
+                continue;
+            };
+            if largest.0 > smallest.0 {
+                let size_difference = largest.0 - smallest.0;
+                let per_word = largest
+                    .1
+                    .saturating_sub(&smallest.1)
+                    .map(|cost| cost.div_ceil(size_difference));
+                program_hash_cost_per_word = program_hash_cost_per_word.max(&per_word);
+            }
+            for (size, cost) in &samples {
+                let fixed_cost = cost.saturating_sub(&program_hash_cost_per_word.scaled(*size));
+                task_overhead = task_overhead.max(&fixed_cost);
+            }
+        }
+
+        Ok(Self {
+            budget,
+            run_options,
+            run_overhead,
+            task_overhead,
+            program_hash_cost_per_word,
+        })
+    }
+
+    /// Estimates the cost added to a bootloader run by a Cairo PIE task.
+    pub fn estimate_pie_task_cost(&self, task: &CairoPieTask) -> RunCost {
+        let mut cost = RunCost::from(&task.cairo_pie.execution_resources);
+        cost += &self.task_overhead;
+        cost += &self.program_hash_cost_per_word.scaled(program_size(task));
+        cost
+    }
+
+    /// Measures the cost added to a bootloader run by a task, by running it alone.
+    pub fn measure_task_cost(&self, task: &TaskSpec) -> Result<RunCost, Error> {
+        let run = run_bootloader(vec![task.clone()], &self.run_options)?;
+        Ok(RunCost::from(&run.resources).saturating_sub(&self.run_overhead))
+    }
+
+    /// Estimates the cost added to a bootloader run by each task. Cairo PIE tasks are estimated
+    /// from their recorded resources, the other tasks are measured.
+    pub fn estimate_task_costs(&self, tasks: &[TaskSpec]) -> Result<Vec<RunCost>, BatchPlanError> {
+        tasks
+            .iter()
+            .enumerate()
+            .map(|(task_index, task)| {
+                let loaded_task = task
+                    .load_task()
+                    .map_err(|e| BatchPlanError::Task(task_index, e))?;
+                match loaded_task.as_any().downcast_ref::<CairoPieTask>() {
+                    Some(pie_task) => Ok(self.estimate_pie_task_cost(pie_task)),
+                    None => self
+                        .measure_task_cost(task)
+                        .map_err(|e| BatchPlanError::Run(task_index, Box::new(e))),
+                }
+            })
+            .collect()
+    }
+
+    /// Packs tasks into runs from their estimated costs.
+    ///
+    /// Tasks keep their order: each run takes as many of the following tasks as fit in the
+    /// budget, so that the outputs of the runs concatenate to the outputs of the whole batch.
+    pub fn plan_from_estimates(&self, task_costs: &[RunCost]) -> Result<BatchPlan, BatchPlanError> {
+        let mut runs: Vec<BatchRun> = vec![];
+
+        for (task_index, cost) in task_costs.iter().enumerate() {
+            if let Some(run) = runs.last_mut() {
+                let mut estimated = run.estimated.clone();
+                estimated += cost;
+                if self.budget.allows(&estimated) {
+                    run.task_indices.push(task_index);
+                    run.estimated = estimated;
+                    continue;
+                }
+            }
+
+            let mut estimated = self.run_overhead.clone();
+            estimated += cost;
+            if !self.budget.allows(&estimated) {
+                return Err(BatchPlanError::TaskTooLarge {
+                    task_index,
+                    estimated,
+                });
+            }
+            runs.push(BatchRun {
+                task_indices: vec![task_index],
+                estimated,
+            });
+        }
+
+        Ok(BatchPlan { runs })
+    }
+
+    pub fn plan(&self, tasks: &[TaskSpec]) -> Result<BatchPlan, BatchPlanError> {
+        let task_costs = self.estimate_task_costs(tasks)?;
+        self.plan_from_estimates(&task_costs)
+    }
+}
+
+/// Number of words of the task program hashed by the bootloader.
+fn program_size(task: &CairoPieTask) -> usize {
+    task.cairo_pie.metadata.program.data.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use cairo_vm::types::program::Program;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{parse_program_input, RunProgramTask};
+
+    fn steps(n_steps: usize) -> RunCost {
+        RunCost {
+            n_steps,
+            builtin_instances: HashMap::new(),
+        }
+    }
+
+    fn planner(budget: BatchBudget) -> BatchPlanner {
+        BatchPlanner {
+            budget,
+            run_options: RunOptions::default(),
+            run_overhead: steps(10),
+            task_overhead: RunCost::default(),
+            program_hash_cost_per_word: RunCost::default(),
+        }
+    }
+
+    fn step_budget(max_steps: usize) -> BatchBudget {
+        BatchBudget {
+            max_steps: Some(max_steps),
+            max_trace_log_size: None,
+        }
+    }
+
+    #[rstest]
+    #[case::empty(vec![], vec![])]
+    #[case::single_run(vec![30, 30, 30], vec![(vec![0, 1, 2], 100)])]
+    #[case::split(vec![50, 40, 20, 75], vec![(vec![0, 1], 100), (vec![2], 30), (vec![3], 85)])]
+    fn test_plan_from_estimates(
+        #[case] task_steps: Vec<usize>,
+        #[case] expected_runs: Vec<(Vec<usize>, usize)>,
+    ) {
+        let task_costs: Vec<RunCost> = task_steps.into_iter().map(steps).collect();
+        let plan = planner(step_budget(100))
+            .plan_from_estimates(&task_costs)
+            .unwrap();
+        let expected_runs: Vec<BatchRun> = expected_runs
+            .into_iter()
+            .map(|(task_indices, n_steps)| BatchRun {
+                task_indices,
+                estimated: steps(n_steps),
+            })
+            .collect();
+        assert_eq!(plan.runs, expected_runs);
+    }
+
+    #[test]
+    fn test_plan_task_too_large() {
+        let result = planner(step_budget(100)).plan_from_estimates(&[steps(10), steps(95)]);
+        assert_matches!(
+            result,
+            Err(BatchPlanError::TaskTooLarge {
+                task_index: 1,
+                estimated
+            }) if estimated == steps(105)
+        );
+    }
+
+    #[test]
+    fn test_plan_with_trace_log_size() {
+        let budget = BatchBudget {
+            max_steps: None,
+            max_trace_log_size: Some(6),
+        };
+        let bitwise_task = |instances| RunCost {
+            n_steps: 10,
+            builtin_instances: HashMap::from([(BuiltinName::bitwise, instances)]),
+        };
+
+        // 64 rows per component: the steps fit, the bitwise instances of the 3 tasks do not.
+        let plan = planner(budget)
+            .plan_from_estimates(&[bitwise_task(30), bitwise_task(30), bitwise_task(30)])
+            .unwrap();
+        let task_indices: Vec<_> = plan.runs.into_iter().map(|run| run.task_indices).collect();
+        assert_eq!(task_indices, vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    fn test_estimate_task_costs_from_pie() {
+        let pie = include_bytes!("../examples/fibonacci_with_output.zip");
+        let tasks = vec![TaskSpec::CairoPieBytes(CairoPieBytes::new(
+            pie.to_vec(),
+            false,
+        ))];
+        let mut planner = planner(step_budget(100));
+        planner.task_overhead = steps(7);
+
+        let task = tasks[0].load_task().unwrap();
+        let task = task.as_any().downcast_ref::<CairoPieTask>().unwrap();
+        let mut expected_cost = RunCost::from(&task.cairo_pie.execution_resources);
+        expected_cost.n_steps += 7;
+
+        assert_eq!(
+            planner.estimate_task_costs(&tasks).unwrap(),
+            vec![expected_cost]
+        );
+    }
+
+    /// The measured estimates must not undercount the runs of the example tasks, alone or together.
+    #[rstest]
+    #[case::default_options(RunOptions::default())]
+    #[case::proof_mode(RunOptions { proof_mode: true, ..RunOptions::default() })]
+    fn test_measured_estimates_cover_example_runs(#[case] options: RunOptions) {
+        let planner = BatchPlanner::measure(BatchBudget::default(), &options).unwrap();
+        let mut pies = CALIBRATION_PIES.to_vec();
+        pies.push(include_bytes!("../examples/sha2.zip"));
+        let mut tasks: Vec<TaskSpec> = pies
+            .into_iter()
+            .map(|pie| TaskSpec::CairoPieBytes(CairoPieBytes::new(pie.to_vec(), false)))
+            .collect();
+        let program = Program::from_bytes(
+            include_bytes!("../examples/fibonacci_with_hint.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let program_input =
+            parse_program_input(include_str!("../examples/fibonacci_input.json")).unwrap();
+        tasks.push(TaskSpec::RunProgram(RunProgramTask::new(
+            program,
+            program_input,
+            false,
+        )));
+
+        let covers = |estimated: &RunCost, tasks: Vec<TaskSpec>| {
+            let run = run_bootloader(tasks, &options).unwrap();
+            let actual = RunCost::from(&run.resources);
+            assert_eq!(
+                actual.saturating_sub(estimated),
+                RunCost::default(),
+                "actual {actual} exceeds estimated {estimated}"
+            );
+        };
+
+        let task_costs = planner.estimate_task_costs(&tasks).unwrap();
+        for (task, task_cost) in tasks.iter().zip(&task_costs) {
+            let mut estimated = planner.run_overhead.clone();
+            estimated += task_cost;
+            covers(&estimated, vec![task.clone()]);
+        }
+
+        let plan = planner.plan_from_estimates(&task_costs).unwrap();
+        assert_matches!(plan.runs.as_slice(), [run] if run.task_indices.len() == tasks.len());
+        covers(&plan.runs[0].estimated, tasks);
+    }
+}
@@ -9,3 +9,609 @@ const BOOTLOADER: &[u8] = include_bytes!("../resources/stwo-bootloader.json");
 pub fn load_bootloader() -> Result<Program, ProgramError> {
     Program::from_bytes(BOOTLOADER, Some("main"))
 }
+
+#[cfg(test)]
+mod tests {
+    use cairo_vm::cairo_run::{cairo_run_program_with_initial_scope, CairoRunConfig};
+    use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintFunc;
+    use cairo_vm::hint_processor::builtin_hint_processor::hint_utils::insert_value_from_var_name;
+    use cairo_vm::hint_processor::hint_processor_definition::{HintProcessor, HintReference};
+    use cairo_vm::serde::deserialize_program::ApTracking;
+    use cairo_vm::types::exec_scope::ExecutionScopes;
+    use cairo_vm::types::layout_name::LayoutName;
+    use cairo_vm::types::relocatable::Relocatable;
+    use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
+    use cairo_vm::vm::errors::hint_errors::HintError;
+    use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+    use cairo_vm::vm::runners::cairo_pie::CairoPie;
+    use cairo_vm::vm::runners::cairo_runner::CairoRunner;
+    use cairo_vm::vm::vm_core::VirtualMachine;
+    use cairo_vm::Felt252;
+    use starknet_crypto::{poseidon_hash_many, FieldElement};
+
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::insert_bootloader_input;
+
+    const FIBONACCI_PIE: &[u8] = include_bytes!("../examples/fibonacci_with_output.zip");
+    const FIBONACCI_WITH_HINT: &[u8] = include_bytes!("../examples/fibonacci_with_hint.json");
+    const FIBONACCI_CLAIM_INDEX_HINT: &str =
+        "ids.fibonacci_claim_index = program_input['fibonacci_claim_index']";
+    /// Replaces `FIBONACCI_CLAIM_INDEX_HINT` in the tasks testing hints that are neither bootloader
+    /// nor program input hints.
+    const CUSTOM_CLAIM_INDEX_HINT: &str = "ids.fibonacci_claim_index = custom_claim_index()";
+
+    /// Stands in for the simple bootloader program hash, which is only compared by the bootloader.
+    const SIMPLE_BOOTLOADER_PROGRAM_HASH: u64 = 0x5b1;
+
+    fn run_bootloader(
+        tasks: Vec<TaskSpec>,
+        packed_outputs: Vec<PackedOutput>,
+        supported_cairo_verifier_program_hashes: Vec<Felt252>,
+    ) -> CairoRunner {
+        try_run_bootloader(
+            tasks,
+            packed_outputs,
+            supported_cairo_verifier_program_hashes,
+        )
+        .expect("Bootloader run failed")
+    }
+
+    fn try_run_bootloader(
+        tasks: Vec<TaskSpec>,
+        packed_outputs: Vec<PackedOutput>,
+        supported_cairo_verifier_program_hashes: Vec<Felt252>,
+    ) -> Result<CairoRunner, CairoRunError> {
+        run_bootloader_with_hint_processor(
+            tasks,
+            packed_outputs,
+            supported_cairo_verifier_program_hashes,
+            &mut BootloaderHintProcessor::new(),
+        )
+    }
+
+    fn run_bootloader_with_hint_processor(
+        tasks: Vec<TaskSpec>,
+        packed_outputs: Vec<PackedOutput>,
+        supported_cairo_verifier_program_hashes: Vec<Felt252>,
+        hint_processor: &mut dyn HintProcessor,
+    ) -> Result<CairoRunner, CairoRunError> {
+        let bootloader_input = BootloaderInput {
+            simple_bootloader_input: SimpleBootloaderInput {
+                fact_topologies_path: None,
+                single_page: false,
+                tasks,
+            },
+            bootloader_config: BootloaderConfig {
+                simple_bootloader_program_hash: Felt252::from(SIMPLE_BOOTLOADER_PROGRAM_HASH),
+                supported_cairo_verifier_program_hashes,
+            },
+            packed_outputs,
+            ignore_fact_topologies: true,
+        };
+        let mut exec_scopes = ExecutionScopes::new();
+        insert_bootloader_input(&mut exec_scopes, bootloader_input);
+
+        let cairo_run_config = CairoRunConfig {
+            entrypoint: "main",
+            layout: LayoutName::all_cairo_stwo,
+            ..Default::default()
+        };
+        cairo_run_program_with_initial_scope(
+            &load_bootloader().unwrap(),
+            &cairo_run_config,
+            hint_processor,
+            exec_scopes,
+        )
+    }
+
+    fn get_output(runner: &mut CairoRunner) -> Vec<Felt252> {
+        let output_base = runner.vm.get_output_builtin_mut().unwrap().base();
+        let output_size = runner
+            .vm
+            .segments
+            .get_segment_used_size(output_base)
+            .unwrap();
+        runner
+            .vm
+            .get_integer_range(Relocatable::from((output_base as isize, 0)), output_size)
+            .unwrap()
+            .into_iter()
+            .map(|felt| *felt)
+            .collect()
+    }
+
+    fn poseidon_hash(elements: &[Felt252]) -> Felt252 {
+        let elements: Vec<FieldElement> = elements
+            .iter()
+            .map(|felt| FieldElement::from_bytes_be(&felt.to_bytes_be()).unwrap())
+            .collect();
+        Felt252::from_bytes_be(&poseidon_hash_many(&elements).to_bytes_be())
+    }
+
+    fn program_task(program: &Program) -> TaskSpec {
+        TaskSpec::RunProgram(RunProgramTask::new(
+            program.clone(),
+            Default::default(),
+            false,
+        ))
+    }
+
+    /// Builds a program standing in for a Cairo verifier: it outputs the `CairoVerifierOutput`
+    /// (simple bootloader program hash, hash of the simple bootloader output) of `outputs`.
+    fn mock_cairo_verifier(outputs: &[Felt252]) -> Program {
+        let program_hash = Felt252::from(SIMPLE_BOOTLOADER_PROGRAM_HASH);
+        let output_hash = poseidon_hash(outputs);
+        let data = [
+            // [ap] = program_hash, ap++;
+            // assert [[fp - 3]] = [ap - 1];
+            Felt252::from(0x480680017fff8000u64),
+            program_hash,
+            Felt252::from(0x400280007ffd7fffu64),
+            // [ap] = output_hash, ap++;
+            // assert [[fp - 3] + 1] = [ap - 1];
+            Felt252::from(0x480680017fff8000u64),
+            output_hash,
+            Felt252::from(0x400280017ffd7fffu64),
+            // [ap] = [fp - 3] + 2, ap++;
+            // ret;
+            Felt252::from(0x482680017ffd8000u64),
+            Felt252::from(2),
+            Felt252::from(0x208b7fff7fff7ffeu64),
+        ];
+        let program_json = serde_json::json!({
+            "prime": "0x800000000000011000000000000000000000000000000000000000000000001",
+            "builtins": ["output"],
+            "data": data.iter().map(|felt| format!("{felt:#x}")).collect::<Vec<_>>(),
+            "hints": {},
+            "identifiers": {
+                "__main__.main": {"type": "function", "pc": 0, "decorators": []}
+            },
+            "main_scope": "__main__",
+            "reference_manager": {"references": []},
+            "attributes": [],
+            "debug_info": null
+        });
+
+        Program::from_bytes(program_json.to_string().as_bytes(), Some("main")).unwrap()
+    }
+
+    /// The program hash is computed in Cairo, so it is read from the output header of a run.
+    fn get_program_hash(program: &Program) -> Felt252 {
+        let mut runner = run_bootloader(
+            vec![program_task(program)],
+            vec![PackedOutput::Plain(vec![])],
+            vec![],
+        );
+        get_output(&mut runner)[2]
+    }
+
+    fn single_page_fact_topology(output_size: usize) -> FactTopology {
+        FactTopology {
+            tree_structure: vec![1, 0],
+            page_sizes: vec![output_size],
+        }
+    }
+
+    /// Runs the bootloader on a plain PIE task and returns its output and PIE.
+    fn run_fibonacci() -> (Vec<Felt252>, CairoPie) {
+        let tasks = vec![TaskSpec::CairoPieBytes(CairoPieBytes::new(
+            FIBONACCI_PIE.to_vec(),
+            false,
+        ))];
+        let mut runner = run_bootloader(tasks, vec![PackedOutput::Plain(vec![])], vec![]);
+        let output = get_output(&mut runner);
+        (output, runner.get_cairo_pie().unwrap())
+    }
+
+    #[test]
+    fn test_bootloader_pie_as_task() {
+        let (fibonacci_output, mut cairo_pie) = run_fibonacci();
+        let mut nested_output = fibonacci_output;
+
+        // Each level runs the PIE of the previous bootloader run as a plain task.
+        for _ in 0..2 {
+            let tasks = vec![TaskSpec::CairoPieTask(CairoPieTask::new(cairo_pie, false))];
+            let mut runner = run_bootloader(tasks, vec![PackedOutput::Plain(vec![])], vec![]);
+            let output = get_output(&mut runner);
+
+            assert_eq!(output[0], Felt252::ONE);
+            assert_eq!(output[1], Felt252::from(nested_output.len() + 2));
+            assert_eq!(&output[3..], nested_output.as_slice());
+
+            nested_output = output;
+            cairo_pie = runner.get_cairo_pie().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_composite_packed_outputs_are_unpacked() {
+        // With a single plain task, the bootloader output is also the simple bootloader output.
+        let (fibonacci_output, _) = run_fibonacci();
+        let fibonacci_task_output_size = fibonacci_output.len() - 3;
+
+        // Level 1: a verifier of the fibonacci bootloader run.
+        let verifier = mock_cairo_verifier(&fibonacci_output);
+        let verifier_hash = get_program_hash(&verifier);
+        let packed_output = CompositePackedOutput::new(
+            fibonacci_output.clone(),
+            vec![PackedOutput::Plain(vec![])],
+            vec![single_page_fact_topology(fibonacci_task_output_size)],
+        );
+        let mut runner = run_bootloader(
+            vec![program_task(&verifier)],
+            vec![PackedOutput::Composite(packed_output.clone())],
+            vec![verifier_hash],
+        );
+        assert_eq!(get_output(&mut runner), fibonacci_output);
+
+        // Level 2: a verifier of the level 1 run, whose simple bootloader output is the header
+        // and output of the level 1 verifier.
+        let simple_bootloader_output = vec![
+            Felt252::ONE,
+            Felt252::from(4),
+            verifier_hash,
+            Felt252::from(SIMPLE_BOOTLOADER_PROGRAM_HASH),
+            poseidon_hash(&fibonacci_output),
+        ];
+        let outer_verifier = mock_cairo_verifier(&simple_bootloader_output);
+        let outer_verifier_hash = get_program_hash(&outer_verifier);
+        let outer_packed_output = CompositePackedOutput::new(
+            simple_bootloader_output,
+            vec![PackedOutput::Composite(packed_output)],
+            vec![single_page_fact_topology(2)],
+        );
+        let mut runner = run_bootloader(
+            vec![program_task(&outer_verifier)],
+            vec![PackedOutput::Composite(outer_packed_output)],
+            vec![verifier_hash, outer_verifier_hash],
+        );
+        assert_eq!(get_output(&mut runner), fibonacci_output);
+    }
+
+    /// Implements `CUSTOM_CLAIM_INDEX_HINT`, which is not part of the bootloader hints.
+    fn fibonacci_claim_index_hint(
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        ids_data: &HashMap<String, HintReference>,
+        ap_tracking: &ApTracking,
+        _constants: &HashMap<String, Felt252>,
+    ) -> Result<(), HintError> {
+        let program_input: &HashMap<String, serde_json::Value> =
+            exec_scopes.get_ref("program_input")?;
+        let claim_index = program_input["fibonacci_claim_index"].as_u64().unwrap();
+        insert_value_from_var_name(
+            "fibonacci_claim_index",
+            Felt252::from(claim_index),
+            vm,
+            ids_data,
+            ap_tracking,
+        )
+    }
+
+    fn fibonacci_program_input() -> HashMap<String, serde_json::Value> {
+        HashMap::from([("fibonacci_claim_index".to_string(), serde_json::json!(10))])
+    }
+
+    /// The Fibonacci program, reading its claim index with `FIBONACCI_CLAIM_INDEX_HINT`.
+    fn fibonacci_with_hint_task() -> TaskSpec {
+        let program = Program::from_bytes(FIBONACCI_WITH_HINT, Some("main")).unwrap();
+        TaskSpec::RunProgram(RunProgramTask::new(
+            program,
+            fibonacci_program_input(),
+            false,
+        ))
+    }
+
+    /// The Fibonacci program, reading its claim index with `CUSTOM_CLAIM_INDEX_HINT`.
+    fn fibonacci_with_custom_hint_task(with_hints: bool) -> TaskSpec {
+        let program_json = std::str::from_utf8(FIBONACCI_WITH_HINT)
+            .unwrap()
+            .replace(FIBONACCI_CLAIM_INDEX_HINT, CUSTOM_CLAIM_INDEX_HINT);
+        let program = Program::from_bytes(program_json.as_bytes(), Some("main")).unwrap();
+        let mut task = RunProgramTask::new(program, fibonacci_program_input(), false);
+        if with_hints {
+            task.add_hint(
+                CUSTOM_CLAIM_INDEX_HINT.to_string(),
+                Rc::new(HintFunc(Box::new(fibonacci_claim_index_hint))),
+            );
+        }
+        TaskSpec::RunProgram(task)
+    }
+
+    #[test]
+    fn test_task_hints() {
+        let tasks = vec![
+            fibonacci_with_custom_hint_task(true),
+            fibonacci_with_custom_hint_task(true),
+        ];
+        let mut runner = run_bootloader(tasks, vec![PackedOutput::Plain(vec![]); 2], vec![]);
+        let output = get_output(&mut runner);
+
+        // Each task outputs the claim index and the corresponding Fibonacci number.
+        assert_eq!(output[0], Felt252::from(2));
+        assert_eq!(output[1], Felt252::from(4));
+        assert_eq!(output[3], Felt252::from(10));
+        assert_eq!(&output[3..5], &output[7..9]);
+    }
+
+    #[test]
+    fn test_task_hints_are_removed_after_the_task() {
+        let tasks = vec![
+            fibonacci_with_custom_hint_task(true),
+            fibonacci_with_custom_hint_task(false),
+        ];
+        let result = try_run_bootloader(tasks, vec![PackedOutput::Plain(vec![]); 2], vec![]);
+        let error = result
+            .err()
+            .expect("The second task should not find the hint of the first one");
+        assert_matches!(
+            &error,
+            CairoRunError::VmException(e) if matches!(
+                &e.inner_exc,
+                VirtualMachineError::Hint(hint_error)
+                    if matches!(&hint_error.1, HintError::UnknownHint(_))
+            ),
+            "Unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    fn test_program_input_hints() {
+        let tasks = vec![fibonacci_with_hint_task(), fibonacci_with_hint_task()];
+        let mut runner = run_bootloader(tasks, vec![PackedOutput::Plain(vec![]); 2], vec![]);
+        let output = get_output(&mut runner);
+
+        assert_eq!(output[0], Felt252::from(2));
+        assert_eq!(output[3], Felt252::from(10));
+        assert_eq!(&output[3..5], &output[7..9]);
+    }
+
+    #[test]
+    fn test_add_program_input_hint() {
+        let mut hint_processor = BootloaderHintProcessor::new();
+        hint_processor.add_program_input_hint(
+            CUSTOM_CLAIM_INDEX_HINT.to_string(),
+            Rc::new(ProgramInputHintFunc(Box::new(
+                |vm, program_input, ids_data, ap_tracking| {
+                    let claim_index = program_input["fibonacci_claim_index"].as_u64().unwrap();
+                    insert_value_from_var_name(
+                        "fibonacci_claim_index",
+                        Felt252::from(claim_index),
+                        vm,
+                        ids_data,
+                        ap_tracking,
+                    )
+                },
+            ))),
+        );
+        let mut runner = run_bootloader_with_hint_processor(
+            vec![fibonacci_with_custom_hint_task(false)],
+            vec![PackedOutput::Plain(vec![])],
+            vec![],
+            &mut hint_processor,
+        )
+        .unwrap();
+        let output = get_output(&mut runner);
+
+        assert_eq!(output[0], Felt252::ONE);
+        assert_eq!(output[3], Felt252::from(10));
+    }
+
+    /// Builds a Cairo 1 executable that outputs its two arguments. It allocates a segment
+    /// before returning to also go through the Cairo 1 hint processor of Cairo VM.
+    fn cairo1_executable() -> Vec<u8> {
+        let bytecode = [
+            // %{ WriteRunParam { index: 0, dst: [ap + 0] } %}
+            // ap += 2;
+            Felt252::from(0x40780017fff7fffu64),
+            Felt252::from(2),
+            // [ap] = [[ap - 2]], ap++;
+            // assert [[fp - 3]] = [ap - 1];
+            Felt252::from(0x480080007ffe8000u64),
+            Felt252::from(0x400280007ffd7fffu64),
+            // [ap] = [[ap - 3] + 1], ap++;
+            // assert [[fp - 3] + 1] = [ap - 1];
+            Felt252::from(0x480080017ffd8000u64),
+            Felt252::from(0x400280017ffd7fffu64),
+            // %{ AllocSegment { dst: [ap + 0] } %}
+            // ap += 1;
+            Felt252::from(0x40780017fff7fffu64),
+            Felt252::from(1),
+            // [ap] = [fp - 3] + 2, ap++;
+            // ret;
+            Felt252::from(0x482680017ffd8000u64),
+            Felt252::from(2),
+            Felt252::from(0x208b7fff7fff7ffeu64),
+        ];
+        let executable = serde_json::json!({
+            "program": {
+                "bytecode": bytecode.iter().map(|felt| format!("{felt:#x}")).collect::<Vec<_>>(),
+                "hints": [
+                    [0, [{"WriteRunParam": {
+                        "index": {"Immediate": "0x0"},
+                        "dst": {"register": "AP", "offset": 0}
+                    }}]],
+                    [6, [{"AllocSegment": {"dst": {"register": "AP", "offset": 0}}}]]
+                ]
+            },
+            "entrypoints": [
+                {"builtins": ["output"], "offset": 0, "kind": "Bootloader"}
+            ]
+        });
+        executable.to_string().into_bytes()
+    }
+
+    #[test]
+    fn test_cairo1_task() {
+        let args = vec![Felt252::from(3), Felt252::from(5)];
+        let task =
+            Cairo1ProgramTask::from_executable(&cairo1_executable(), args.clone(), false).unwrap();
+
+        let mut runner = run_bootloader(
+            vec![TaskSpec::RunCairo1Program(task)],
+            vec![PackedOutput::Plain(vec![])],
+            vec![],
+        );
+        let output = get_output(&mut runner);
+
+        assert_eq!(output[0], Felt252::ONE);
+        assert_eq!(output[1], Felt252::from(4));
+        assert_eq!(&output[3..], args.as_slice());
+    }
+
+    #[test]
+    fn test_hint_profiler() {
+        let tasks = vec![
+            fibonacci_with_custom_hint_task(true),
+            fibonacci_with_custom_hint_task(true),
+        ];
+        let mut profiler = HintProfiler::new(BootloaderHintProcessor::new());
+        run_bootloader_with_hint_processor(
+            tasks,
+            vec![PackedOutput::Plain(vec![]); 2],
+            vec![],
+            &mut profiler,
+        )
+        .unwrap();
+
+        let profile = profiler.profile();
+        let get_calls = |task_index: Option<usize>, hint: &str| {
+            profile
+                .entries
+                .iter()
+                .find(|entry| entry.task_index == task_index && entry.hint.starts_with(hint))
+                .map(|entry| entry.stats.calls)
+        };
+        assert_eq!(get_calls(None, "CallTask"), Some(2));
+        assert_eq!(get_calls(Some(0), "ids.fibonacci_claim_index"), Some(1));
+        assert_eq!(get_calls(Some(1), "ids.fibonacci_claim_index"), Some(1));
+    }
+
+    fn fibonacci_pie_task(max_steps: Option<usize>) -> TaskSpec {
+        let mut task = TaskSpec::CairoPieBytes(CairoPieBytes::new(FIBONACCI_PIE.to_vec(), false));
+        task.set_max_steps(max_steps);
+        task
+    }
+
+    #[test]
+    fn test_task_step_limit() {
+        let tasks = vec![fibonacci_pie_task(None), fibonacci_pie_task(Some(10))];
+        let mut hint_processor = BootloaderHintProcessor::new();
+        let result = run_bootloader_with_hint_processor(
+            tasks,
+            vec![PackedOutput::Plain(vec![]); 2],
+            vec![],
+            &mut hint_processor,
+        );
+
+        assert!(result.is_err());
+        assert_matches!(
+            hint_processor.step_limit_exceeded(),
+            Some(StepLimitExceeded::Task {
+                task: TaskContext {
+                    task_index: 1,
+                    kind: TaskKind::CairoPie,
+                    program_hash: Some(_),
+                    ..
+                },
+                max_steps: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn test_run_step_limit() {
+        let mut hint_processor = BootloaderHintProcessor::new();
+        hint_processor.set_max_steps(Some(10));
+        let result = run_bootloader_with_hint_processor(
+            vec![fibonacci_pie_task(None)],
+            vec![PackedOutput::Plain(vec![])],
+            vec![],
+            &mut hint_processor,
+        );
+
+        // The bootloader runs more than 10 steps before calling the first task.
+        assert!(result.is_err());
+        assert_eq!(
+            hint_processor.step_limit_exceeded(),
+            Some(&StepLimitExceeded::Run {
+                task: None,
+                max_steps: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn test_current_task_of_failed_run() {
+        // The second task does not carry the implementation of its hint.
+        let tasks = vec![
+            fibonacci_with_custom_hint_task(true),
+            fibonacci_with_custom_hint_task(false),
+        ];
+        let mut hint_processor = BootloaderHintProcessor::new();
+        let result = run_bootloader_with_hint_processor(
+            tasks,
+            vec![PackedOutput::Plain(vec![]); 2],
+            vec![],
+            &mut hint_processor,
+        );
+
+        assert!(result.is_err());
+        assert_matches!(
+            hint_processor.current_task(),
+            Some(TaskContext {
+                task_index: 1,
+                kind: TaskKind::Program,
+                source_path: None,
+                program_hash: Some(_),
+            })
+        );
+    }
+
+    #[test]
+    fn test_repeated_task_hints_are_compiled_once() {
+        let tasks = vec![fibonacci_with_hint_task(), fibonacci_with_hint_task()];
+        let mut hint_processor = BootloaderHintProcessor::new();
+        run_bootloader_with_hint_processor(
+            tasks,
+            vec![PackedOutput::Plain(vec![]); 2],
+            vec![],
+            &mut hint_processor,
+        )
+        .expect("Bootloader run failed");
+
+        // Both tasks have the same program hash, the second one reuses the hints of the first.
+        assert_eq!(hint_processor.n_cached_task_programs(), 1);
+    }
+
+    #[test]
+    fn test_current_task_after_task_return() {
+        // The PIE records one step more than its execution, which fails once the task returned.
+        let mut cairo_pie = CairoPie::from_bytes(FIBONACCI_PIE).unwrap();
+        cairo_pie.execution_resources.n_steps += 1;
+        let tasks = vec![TaskSpec::CairoPieTask(CairoPieTask::new(cairo_pie, false))];
+        let mut hint_processor = BootloaderHintProcessor::new();
+        let result = run_bootloader_with_hint_processor(
+            tasks,
+            vec![PackedOutput::Plain(vec![])],
+            vec![],
+            &mut hint_processor,
+        );
+
+        assert!(result.is_err());
+        assert_matches!(
+            hint_processor.current_task(),
+            Some(TaskContext {
+                task_index: 0,
+                kind: TaskKind::CairoPie,
+                program_hash: Some(_),
+                ..
+            })
+        );
+    }
+}
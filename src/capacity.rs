@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use cairo_vm::vm::errors::runner_errors::RunnerError;
+use cairo_vm::vm::runners::cairo_runner::CairoRunner;
+use serde::Serialize;
+
+/// Usage of a trace or builtin component once padded by stwo to the next power of two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CapacityUsage {
+    pub used: usize,
+    pub padded: usize,
+}
+
+impl CapacityUsage {
+    /// Unused components are not padded at all.
+    pub fn new(used: usize) -> Self {
+        let padded = if used == 0 {
+            0
+        } else {
+            used.next_power_of_two()
+        };
+        Self { used, padded }
+    }
+
+    /// Rows (steps or instances) that can be added without reaching the next size step.
+    pub fn free(&self) -> usize {
+        self.padded - self.used
+    }
+
+    /// Share of the padded size that is padding, between 0 and 1.
+    pub fn wasted_share(&self) -> f64 {
+        if self.padded == 0 {
+            0.0
+        } else {
+            self.free() as f64 / self.padded as f64
+        }
+    }
+}
+
+impl fmt::Display for CapacityUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} / {} ({:.1}% padding, {} free)",
+            self.used,
+            self.padded,
+            self.wasted_share() * 100.0,
+            self.free()
+        )
+    }
+}
+
+/// Capacity usage of the main trace and of each builtin of a run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CapacityReport {
+    pub steps: CapacityUsage,
+    /// Instances of each builtin of the program, including the unused ones.
+    pub builtins: BTreeMap<&'static str, CapacityUsage>,
+}
+
+impl CapacityReport {
+    /// Reads the usage of the main trace and of the builtins of the runner's layout.
+    pub fn from_runner(runner: &CairoRunner) -> Result<Self, RunnerError> {
+        let resources = runner.get_execution_resources()?;
+        let builtins = runner
+            .vm
+            .builtin_runners
+            .iter()
+            .map(|builtin_runner| {
+                let builtin = builtin_runner.name();
+                let used = resources
+                    .builtin_instance_counter
+                    .get(&builtin)
+                    .copied()
+                    .unwrap_or(0);
+                (builtin.to_str(), CapacityUsage::new(used))
+            })
+            .collect();
+
+        Ok(Self {
+            steps: CapacityUsage::new(resources.n_steps),
+            builtins,
+        })
+    }
+}
+
+impl fmt::Display for CapacityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Capacity:")?;
+        write!(f, "\n  steps: {}", self.steps)?;
+        for (builtin, usage) in &self.builtins {
+            write!(f, "\n  {builtin}: {usage}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::unused(0, 0, 0)]
+    #[case::one(1, 1, 0)]
+    #[case::power_of_two(1024, 1024, 0)]
+    #[case::above_power_of_two(1025, 2048, 1023)]
+    fn test_capacity_usage(#[case] used: usize, #[case] padded: usize, #[case] free: usize) {
+        let usage = CapacityUsage::new(used);
+        assert_eq!(usage.padded, padded);
+        assert_eq!(usage.free(), free);
+    }
+
+    #[test]
+    fn test_capacity_usage_display() {
+        let usage = CapacityUsage::new(3);
+        assert_eq!(usage.wasted_share(), 0.25);
+        assert_eq!(usage.to_string(), "3 / 4 (25.0% padding, 1 free)");
+        assert_eq!(CapacityUsage::new(0).wasted_share(), 0.0);
+    }
+}
@@ -0,0 +1,238 @@
+use cairo_vm::serde::deserialize_program::Location;
+use cairo_vm::types::errors::program_errors::ProgramError;
+use cairo_vm::types::relocatable::Relocatable;
+use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::errors::runner_errors::RunnerError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+use cairo_vm::Felt252;
+
+use crate::batch::BatchPlanError;
+use crate::prover_input::ProverInputError;
+use crate::run::BootloaderOutputError;
+use crate::tasks::BootloaderTaskError;
+use crate::{
+    BootloaderHintProcessor, Cairo1TaskError, CairoPieLoaderError, FactTopologyError,
+    PieResourcesReport, ProgramHashError, ProgramLoaderError, StepLimitExceeded, TaskContext,
+    TaskExecutionError, UnsupportedHintsError, WriteFactTopologiesError,
+};
+
+/// Errors of the crate.
+///
+/// Errors raised by the bootloader hints go through the VM as
+/// `HintError::Internal(VirtualMachineError::Other(_))` and are taken back out of the
+/// `CairoRunError` by `Error::from`, in an `Error::Hint` keeping the location of the hint.
+/// `Error::root_cause` gives the error of the hint for callers to match on its variant.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Task(#[from] BootloaderTaskError),
+
+    #[error(transparent)]
+    ProgramHash(#[from] ProgramHashError),
+
+    #[error("Computed program hash {computed:#x} does not match input {expected:#x}")]
+    ProgramHashMismatch {
+        expected: Felt252,
+        computed: Felt252,
+    },
+
+    #[error(transparent)]
+    ProgramLoader(#[from] ProgramLoaderError),
+
+    #[error(transparent)]
+    CairoPieLoader(#[from] CairoPieLoaderError),
+
+    #[error("Inconsistent Cairo PIE resources: {0}")]
+    PieResources(PieResourcesReport),
+
+    #[error(transparent)]
+    Cairo1Task(#[from] Cairo1TaskError),
+
+    #[error(transparent)]
+    FactTopology(#[from] FactTopologyError),
+
+    #[error(transparent)]
+    WriteFactTopologies(#[from] WriteFactTopologiesError),
+
+    #[error(transparent)]
+    StepLimitExceeded(#[from] StepLimitExceeded),
+
+    #[error(transparent)]
+    UnsupportedHints(#[from] UnsupportedHintsError),
+
+    /// A task that is neither a Cairo 0 program, a Cairo 1 program nor a Cairo PIE.
+    #[error("Unexpected task type")]
+    UnexpectedTaskType,
+
+    /// An error raised by a bootloader hint, with the location of the hint in the Cairo code.
+    #[error("Error at pc={pc}: {source}")]
+    Hint {
+        source: Box<Error>,
+        pc: Relocatable,
+        inst_location: Option<Location>,
+        traceback: Option<String>,
+    },
+
+    /// A run that failed while executing one of its tasks.
+    #[error("Failed to execute {task}: {source}")]
+    TaskExecution {
+        task: TaskContext,
+        source: Box<Error>,
+    },
+
+    #[error("Failed to load the bootloader: {0}")]
+    Bootloader(#[from] ProgramError),
+
+    #[error(transparent)]
+    Run(CairoRunError),
+
+    #[error("Failed to read the execution resources: {0}")]
+    Resources(#[from] RunnerError),
+
+    #[error("Failed to decode the bootloader output: {0}")]
+    Output(#[from] BootloaderOutputError),
+
+    #[error(transparent)]
+    BatchPlan(#[from] BatchPlanError),
+
+    #[error(transparent)]
+    ProverInput(#[from] ProverInputError),
+}
+
+impl Error {
+    /// The error behind the run context added by `TaskExecution` and `Hint`.
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Error::TaskExecution { source, .. } | Error::Hint { source, .. } => source.root_cause(),
+            error => error,
+        }
+    }
+
+    /// Names the step limit or the task behind the failure of a run with `hint_processor`.
+    pub fn from_run_error(error: CairoRunError, hint_processor: &BootloaderHintProcessor) -> Self {
+        // The VM only reports an unfinished execution, the hint processor knows the limit hit.
+        if let Some(step_limit_exceeded) = hint_processor.step_limit_exceeded() {
+            return step_limit_exceeded.clone().into();
+        }
+        match hint_processor.current_task() {
+            Some(task) => TaskExecutionError {
+                task: task.clone(),
+                source: error,
+            }
+            .into(),
+            None => error.into(),
+        }
+    }
+}
+
+impl From<Error> for HintError {
+    fn from(value: Error) -> Self {
+        HintError::Internal(VirtualMachineError::Other(anyhow::Error::new(value)))
+    }
+}
+
+/// Takes the error raised by a bootloader hint out of `error`, gives `error` back otherwise.
+fn take_hint_source(error: VirtualMachineError) -> Result<Error, VirtualMachineError> {
+    match error {
+        VirtualMachineError::Hint(hint_error) => {
+            let (hint_index, hint_error) = *hint_error;
+            match hint_error {
+                HintError::Internal(inner) => take_hint_source(inner).map_err(|inner| {
+                    VirtualMachineError::Hint(Box::new((hint_index, HintError::Internal(inner))))
+                }),
+                hint_error => Err(VirtualMachineError::Hint(Box::new((
+                    hint_index, hint_error,
+                )))),
+            }
+        }
+        VirtualMachineError::Other(error) => error
+            .downcast::<Error>()
+            .map_err(VirtualMachineError::Other),
+        error => Err(error),
+    }
+}
+
+impl From<CairoRunError> for Error {
+    fn from(error: CairoRunError) -> Self {
+        let CairoRunError::VmException(mut vm_exception) = error else {
+            return Error::Run(error);
+        };
+        let inner_exc =
+            std::mem::replace(&mut vm_exception.inner_exc, VirtualMachineError::Unexpected);
+        match take_hint_source(inner_exc) {
+            Ok(error) => Error::Hint {
+                source: Box::new(error),
+                pc: vm_exception.pc,
+                inst_location: vm_exception.inst_location,
+                traceback: vm_exception.traceback,
+            },
+            Err(inner_exc) => {
+                vm_exception.inner_exc = inner_exc;
+                Error::Run(CairoRunError::VmException(vm_exception))
+            }
+        }
+    }
+}
+
+impl From<TaskExecutionError> for Error {
+    fn from(error: TaskExecutionError) -> Self {
+        Error::TaskExecution {
+            task: error.task,
+            source: Box::new(error.source.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use cairo_vm::vm::errors::vm_exception::VmException;
+
+    use super::*;
+
+    fn vm_exception(inner_exc: VirtualMachineError) -> CairoRunError {
+        CairoRunError::VmException(VmException {
+            pc: Relocatable::from((0, 24)),
+            inst_location: None,
+            inner_exc,
+            error_attr_value: None,
+            traceback: Some("Cairo traceback (most recent call last):".to_string()),
+        })
+    }
+
+    #[test]
+    fn test_hint_error_source_is_preserved() {
+        let hint_error: HintError = Error::ProgramHashMismatch {
+            expected: Felt252::from(1),
+            computed: Felt252::from(2),
+        }
+        .into();
+        let error = vm_exception(VirtualMachineError::Hint(Box::new((0, hint_error))));
+
+        let error = Error::from(error);
+        assert_matches!(
+            error.root_cause(),
+            Error::ProgramHashMismatch { expected, computed }
+                if *expected == Felt252::from(1) && *computed == Felt252::from(2)
+        );
+        assert_matches!(
+            error,
+            Error::Hint { pc, traceback: Some(_), .. } if pc == Relocatable::from((0, 24))
+        );
+    }
+
+    #[test]
+    fn test_other_run_errors_are_kept() {
+        let hint_error = HintError::UnknownHint("unknown_hint()".into());
+        let error = vm_exception(VirtualMachineError::Hint(Box::new((0, hint_error))));
+
+        assert_matches!(
+            Error::from(error),
+            Error::Run(CairoRunError::VmException(VmException {
+                inner_exc: VirtualMachineError::Hint(hint_error),
+                ..
+            })) if matches!(hint_error.1, HintError::UnknownHint(_))
+        );
+    }
+}
@@ -112,7 +112,10 @@ pub fn restore_bootloader_output(
 /// (N, 2): N+1     # Pointer to the new segment
 /// (N+1, 0): 3     # Write the values of the nested vector
 /// (N+1, 1): 4
-fn gen_arg(vm: &mut VirtualMachine, args: &Vec<Box<dyn Any>>) -> Result<Relocatable, MemoryError> {
+pub(crate) fn gen_arg(
+    vm: &mut VirtualMachine,
+    args: &Vec<Box<dyn Any>>,
+) -> Result<Relocatable, MemoryError> {
     let base = vm.segments.add();
     let mut ptr = base;
 
@@ -338,6 +341,7 @@ pub fn compute_and_configure_fact_topologies(
         write_to_fact_topologies_file(path.as_path(), &plain_fact_topologies)
             .map_err(Into::<HintError>::into)?;
     }
+    exec_scopes.insert_value(vars::PLAIN_FACT_TOPOLOGIES, plain_fact_topologies);
 
     Ok(HashMap::new())
 }
@@ -919,6 +923,7 @@ mod tests {
             PackedOutput::Composite(CompositePackedOutput {
                 outputs: vec![],
                 subtasks: subtasks.clone(),
+                fact_topologies: vec![],
             }),
         );
 
@@ -956,6 +961,7 @@ mod tests {
             Box::new(PackedOutput::Composite(CompositePackedOutput {
                 outputs: vec![Felt252::from(42)],
                 subtasks: vec![],
+                fact_topologies: vec![],
             })),
         );
 
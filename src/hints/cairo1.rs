@@ -0,0 +1,261 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::Path;
+
+use cairo_lang_casm::hints::{ExternalHint, Hint};
+use cairo_lang_casm::operand::{CellRef, Register, ResOperand};
+use cairo_vm::hint_processor::cairo_1_hint_processor::hint_processor::Cairo1HintProcessor;
+use cairo_vm::hint_processor::hint_processor_definition::{HintExtension, HintProcessorLogic};
+use cairo_vm::serde::deserialize_program::{
+    ApTracking, FlowTrackingData, HintParams, ReferenceManager,
+};
+use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::types::errors::program_errors::ProgramError;
+use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::types::program::Program;
+use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::runners::cairo_runner::RunResources;
+use cairo_vm::vm::vm_core::VirtualMachine;
+use cairo_vm::Felt252;
+use num_traits::ToPrimitive;
+use serde::Deserialize;
+
+use crate::hints::types::Cairo1ProgramTask;
+
+#[derive(thiserror_no_std::Error, Debug)]
+pub enum Cairo1TaskError {
+    #[error("Failed to read the executable: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse the executable: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    #[error("The executable has no bootloader entry point")]
+    NoBootloaderEntryPoint,
+
+    #[error("Invalid executable program: {0}")]
+    Program(#[from] ProgramError),
+}
+
+/// The parts of a Cairo 1 executable (as written by `cairo-execute --build-only` or
+/// `scarb build` with an executable target) needed to run it as a task.
+#[derive(Deserialize, Debug)]
+struct Executable {
+    program: AssembledProgram,
+    entrypoints: Vec<ExecutableEntryPoint>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AssembledProgram {
+    bytecode: Vec<Felt252>,
+    hints: Vec<(usize, Vec<Hint>)>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ExecutableEntryPoint {
+    builtins: Vec<BuiltinName>,
+    offset: usize,
+    kind: EntryPointKind,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+enum EntryPointKind {
+    /// Takes and returns the builtin pointers like the `main` function of a Cairo 0 program.
+    Bootloader,
+    /// Allocates its own builtin segments, cannot be called by the bootloader.
+    Standalone,
+}
+
+impl Cairo1ProgramTask {
+    /// Creates a task from the content of a Cairo 1 executable file.
+    ///
+    /// `args` are the arguments of the executable function, serialized as felts like the `--args`
+    /// of `cairo-execute`.
+    pub fn from_executable(
+        executable: &[u8],
+        args: Vec<Felt252>,
+        use_poseidon: bool,
+    ) -> Result<Self, Cairo1TaskError> {
+        let executable: Executable = serde_json::from_slice(executable)?;
+        let entrypoint = executable
+            .entrypoints
+            .into_iter()
+            .find(|entrypoint| entrypoint.kind == EntryPointKind::Bootloader)
+            .ok_or(Cairo1TaskError::NoBootloaderEntryPoint)?;
+
+        let data: Vec<MaybeRelocatable> = executable
+            .program
+            .bytecode
+            .into_iter()
+            .map(MaybeRelocatable::from)
+            .collect();
+        // Hint codes are the hint offsets, `Cairo1TaskHintProcessor` maps them back to the hints.
+        let program_hints: HashMap<usize, Vec<HintParams>> = executable
+            .program
+            .hints
+            .iter()
+            .map(|(offset, _)| {
+                let hint_params = HintParams {
+                    code: offset.to_string(),
+                    accessible_scopes: vec![],
+                    flow_tracking_data: FlowTrackingData {
+                        ap_tracking: ApTracking::new(),
+                        reference_ids: HashMap::new(),
+                    },
+                };
+                (*offset, vec![hint_params])
+            })
+            .collect();
+
+        let program = Program::new(
+            entrypoint.builtins,
+            data,
+            Some(entrypoint.offset),
+            program_hints,
+            ReferenceManager { references: vec![] },
+            HashMap::new(),
+            vec![],
+            None,
+        )?;
+
+        Ok(Self {
+            program,
+            hints: executable.program.hints,
+            args,
+            use_poseidon,
+            max_steps: None,
+        })
+    }
+
+    pub fn from_executable_file(
+        path: &Path,
+        args: Vec<Felt252>,
+        use_poseidon: bool,
+    ) -> Result<Self, Cairo1TaskError> {
+        let executable = std::fs::read(path)?;
+        Self::from_executable(&executable, args, use_poseidon)
+    }
+}
+
+/// Executes the hints of a Cairo 1 task.
+///
+/// Core hints are delegated to the Cairo 1 hint processor of Cairo VM. `WriteRunParam`, which
+/// passes the arguments to the executable function, is implemented here as it depends on the
+/// task.
+pub(crate) struct Cairo1TaskHintProcessor {
+    hints: HashMap<usize, Vec<Hint>>,
+    args: Vec<Felt252>,
+    cairo1_hint_processor: Cairo1HintProcessor,
+}
+
+impl Cairo1TaskHintProcessor {
+    pub(crate) fn new(task: &Cairo1ProgramTask) -> Self {
+        Self {
+            hints: task.hints.iter().cloned().collect(),
+            args: task.args.clone(),
+            cairo1_hint_processor: Cairo1HintProcessor::new(
+                &task.hints,
+                RunResources::default(),
+                false,
+            ),
+        }
+    }
+
+    pub(crate) fn execute_hint(
+        &mut self,
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        hint_code: &str,
+        constants: &HashMap<String, Felt252>,
+    ) -> Result<HintExtension, HintError> {
+        let hints = hint_code
+            .parse::<usize>()
+            .ok()
+            .and_then(|offset| self.hints.get(&offset))
+            .ok_or_else(|| HintError::UnknownHint(hint_code.to_string().into_boxed_str()))?;
+
+        for hint in hints {
+            match hint {
+                Hint::External(ExternalHint::WriteRunParam { index, dst }) => {
+                    write_run_param(vm, &self.args, index, dst)?
+                }
+                hint => {
+                    let hint_data: Box<dyn Any> = Box::new(vec![hint.clone()]);
+                    self.cairo1_hint_processor.execute_hint(
+                        vm,
+                        exec_scopes,
+                        &hint_data,
+                        constants,
+                    )?
+                }
+            }
+        }
+
+        Ok(HashMap::new())
+    }
+}
+
+fn cell_ref_to_relocatable(
+    vm: &VirtualMachine,
+    cell_ref: &CellRef,
+) -> Result<Relocatable, HintError> {
+    let base = match cell_ref.register {
+        Register::AP => vm.get_ap(),
+        Register::FP => vm.get_fp(),
+    };
+    Ok((base + i32::from(cell_ref.offset))?)
+}
+
+/// Writes the arguments array of the executable function, as `cairo-execute` does: the
+/// arguments are stored in a new segment and `dst` receives the start and end pointers.
+fn write_run_param(
+    vm: &mut VirtualMachine,
+    args: &[Felt252],
+    index: &ResOperand,
+    dst: &CellRef,
+) -> Result<(), HintError> {
+    let index = match index {
+        ResOperand::Immediate(value) => value.value.to_usize(),
+        ResOperand::Deref(cell_ref) => {
+            let address = cell_ref_to_relocatable(vm, cell_ref)?;
+            vm.get_integer(address)?.to_usize()
+        }
+        _ => None,
+    };
+    if index != Some(0) {
+        return Err(HintError::CustomHint(
+            "Cairo 1 tasks only take a single argument array"
+                .to_string()
+                .into_boxed_str(),
+        ));
+    }
+
+    let args: Vec<MaybeRelocatable> = args.iter().copied().map(MaybeRelocatable::from).collect();
+    let args_start = vm.add_memory_segment();
+    let args_end = vm.load_data(args_start, &args)?;
+
+    let dst = cell_ref_to_relocatable(vm, dst)?;
+    vm.insert_value(dst, args_start)?;
+    vm.insert_value((dst + 1)?, args_end)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_executable_without_bootloader_entry_point() {
+        let executable = serde_json::json!({
+            "program": {"bytecode": ["0x208b7fff7fff7ffe"], "hints": []},
+            "entrypoints": [{"builtins": [], "offset": 0, "kind": "Standalone"}]
+        });
+
+        let result =
+            Cairo1ProgramTask::from_executable(executable.to_string().as_bytes(), vec![], false);
+        assert_matches!(result, Err(Cairo1TaskError::NoBootloaderEntryPoint));
+    }
+}
@@ -0,0 +1,219 @@
+use cairo_vm::hint_processor::builtin_hint_processor::hint_code;
+
+/// The hints matched by `BuiltinHintProcessor::execute_hint`, without the `test_utils` ones.
+///
+/// `BuiltinHintProcessor` does not list the hints it implements, so this list follows
+/// `builtin_hint_processor_definition.rs` and has to be updated with the Cairo VM version.
+const CAIRO_VM_HINT_CODES: [&str; 190] = [
+    hint_code::ADD_SEGMENT,
+    hint_code::IS_NN,
+    hint_code::IS_NN_OUT_OF_RANGE,
+    hint_code::ASSERT_LE_FELT,
+    hint_code::ASSERT_LE_FELT_EXCLUDED_2,
+    hint_code::ASSERT_LE_FELT_EXCLUDED_1,
+    hint_code::ASSERT_LE_FELT_EXCLUDED_0,
+    hint_code::IS_LE_FELT,
+    hint_code::ASSERT_250_BITS,
+    hint_code::IS_250_BITS,
+    hint_code::IS_ADDR_BOUNDED,
+    hint_code::IS_POSITIVE,
+    hint_code::SPLIT_INT_ASSERT_RANGE,
+    hint_code::SPLIT_INT,
+    hint_code::ASSERT_NOT_EQUAL,
+    hint_code::ASSERT_NN,
+    hint_code::SQRT,
+    hint_code::ASSERT_NOT_ZERO,
+    hint_code::IS_QUAD_RESIDUE,
+    hint_code::VM_EXIT_SCOPE,
+    hint_code::MEMCPY_ENTER_SCOPE,
+    hint_code::MEMSET_ENTER_SCOPE,
+    hint_code::MEMCPY_CONTINUE_COPYING,
+    hint_code::MEMSET_CONTINUE_LOOP,
+    hint_code::SPLIT_FELT,
+    hint_code::UNSIGNED_DIV_REM,
+    hint_code::SIGNED_DIV_REM,
+    hint_code::ASSERT_LT_FELT,
+    hint_code::FIND_ELEMENT,
+    hint_code::SEARCH_SORTED_LOWER,
+    hint_code::POW,
+    hint_code::SET_ADD,
+    hint_code::DICT_NEW,
+    hint_code::DICT_READ,
+    hint_code::DICT_WRITE,
+    hint_code::DEFAULT_DICT_NEW,
+    hint_code::SQUASH_DICT_INNER_FIRST_ITERATION,
+    hint_code::USORT_ENTER_SCOPE,
+    hint_code::USORT_BODY,
+    hint_code::USORT_VERIFY,
+    hint_code::USORT_VERIFY_MULTIPLICITY_ASSERT,
+    hint_code::USORT_VERIFY_MULTIPLICITY_BODY,
+    hint_code::BLAKE2S_COMPUTE,
+    hint_code::VERIFY_ZERO_V1,
+    hint_code::VERIFY_ZERO_V2,
+    hint_code::VERIFY_ZERO_V3,
+    hint_code::VERIFY_ZERO_EXTERNAL_SECP,
+    hint_code::NONDET_BIGINT3_V1,
+    hint_code::NONDET_BIGINT3_V2,
+    hint_code::REDUCE_V1,
+    hint_code::REDUCE_V2,
+    hint_code::REDUCE_ED25519,
+    hint_code::BLAKE2S_FINALIZE,
+    hint_code::BLAKE2S_FINALIZE_V2,
+    hint_code::BLAKE2S_FINALIZE_V3,
+    hint_code::BLAKE2S_ADD_UINT256,
+    hint_code::BLAKE2S_ADD_UINT256_BIGEND,
+    hint_code::UNSAFE_KECCAK,
+    hint_code::UNSAFE_KECCAK_FINALIZE,
+    hint_code::SQUASH_DICT_INNER_SKIP_LOOP,
+    hint_code::SQUASH_DICT_INNER_CHECK_ACCESS_INDEX,
+    hint_code::SQUASH_DICT_INNER_CONTINUE_LOOP,
+    hint_code::SQUASH_DICT_INNER_ASSERT_LEN_KEYS,
+    hint_code::SQUASH_DICT_INNER_LEN_ASSERT,
+    hint_code::SQUASH_DICT_INNER_USED_ACCESSES_ASSERT,
+    hint_code::SQUASH_DICT_INNER_NEXT_KEY,
+    hint_code::SQUASH_DICT,
+    hint_code::VM_ENTER_SCOPE,
+    hint_code::DICT_UPDATE,
+    hint_code::DICT_SQUASH_COPY_DICT,
+    hint_code::DICT_SQUASH_UPDATE_PTR,
+    hint_code::UINT256_ADD,
+    hint_code::UINT256_ADD_LOW,
+    hint_code::UINT128_ADD,
+    hint_code::UINT256_SUB,
+    hint_code::SPLIT_64,
+    hint_code::UINT256_SQRT,
+    hint_code::UINT256_SQRT_FELT,
+    hint_code::UINT256_SIGNED_NN,
+    hint_code::UINT256_UNSIGNED_DIV_REM,
+    hint_code::UINT256_EXPANDED_UNSIGNED_DIV_REM,
+    hint_code::BIGINT_TO_UINT256,
+    hint_code::IS_ZERO_PACK_V1,
+    hint_code::IS_ZERO_PACK_V2,
+    hint_code::IS_ZERO_NONDET,
+    hint_code::IS_ZERO_INT,
+    hint_code::IS_ZERO_PACK_EXTERNAL_SECP_V1,
+    hint_code::IS_ZERO_PACK_EXTERNAL_SECP_V2,
+    hint_code::IS_ZERO_PACK_ED25519,
+    hint_code::IS_ZERO_ASSIGN_SCOPE_VARS,
+    hint_code::IS_ZERO_ASSIGN_SCOPE_VARS_EXTERNAL_SECP,
+    hint_code::IS_ZERO_ASSIGN_SCOPE_VARS_ED25519,
+    hint_code::DIV_MOD_N_PACKED_DIVMOD_V1,
+    hint_code::GET_FELT_BIT_LENGTH,
+    hint_code::BIGINT_PACK_DIV_MOD,
+    hint_code::BIGINT_SAFE_DIV,
+    hint_code::DIV_MOD_N_PACKED_DIVMOD_EXTERNAL_N,
+    hint_code::DIV_MOD_N_SAFE_DIV,
+    hint_code::DIV_MOD_N_SAFE_DIV_PLUS_ONE,
+    hint_code::GET_POINT_FROM_X,
+    hint_code::EC_NEGATE,
+    hint_code::EC_NEGATE_EMBEDDED_SECP,
+    hint_code::EC_DOUBLE_SLOPE_V1,
+    hint_code::EC_DOUBLE_SLOPE_V2,
+    hint_code::EC_DOUBLE_SLOPE_V3,
+    hint_code::EC_DOUBLE_SLOPE_V4,
+    hint_code::EC_DOUBLE_SLOPE_EXTERNAL_CONSTS,
+    hint_code::COMPUTE_SLOPE_V1,
+    hint_code::SQUARE_SLOPE_X_MOD_P,
+    hint_code::COMPUTE_SLOPE_V2,
+    hint_code::COMPUTE_SLOPE_SECP256R1_V1,
+    hint_code::COMPUTE_SLOPE_SECP256R1_V2,
+    hint_code::IMPORT_SECP256R1_P,
+    hint_code::COMPUTE_SLOPE_WHITELIST,
+    hint_code::EC_DOUBLE_ASSIGN_NEW_X_V1,
+    hint_code::EC_DOUBLE_ASSIGN_NEW_X_V2,
+    hint_code::EC_DOUBLE_ASSIGN_NEW_X_V3,
+    hint_code::EC_DOUBLE_ASSIGN_NEW_X_V4,
+    hint_code::EC_DOUBLE_ASSIGN_NEW_Y,
+    hint_code::KECCAK_WRITE_ARGS,
+    hint_code::COMPARE_BYTES_IN_WORD_NONDET,
+    hint_code::SHA256_MAIN_CONSTANT_INPUT_LENGTH,
+    hint_code::SHA256_MAIN_ARBITRARY_INPUT_LENGTH,
+    hint_code::SHA256_INPUT,
+    hint_code::SHA256_FINALIZE,
+    hint_code::CAIRO_KECCAK_INPUT_IS_FULL_WORD,
+    hint_code::COMPARE_KECCAK_FULL_RATE_IN_BYTES_NONDET,
+    hint_code::BLOCK_PERMUTATION,
+    hint_code::BLOCK_PERMUTATION_WHITELIST_V1,
+    hint_code::BLOCK_PERMUTATION_WHITELIST_V2,
+    hint_code::CAIRO_KECCAK_FINALIZE_V1,
+    hint_code::CAIRO_KECCAK_FINALIZE_V2,
+    hint_code::FAST_EC_ADD_ASSIGN_NEW_X,
+    hint_code::FAST_EC_ADD_ASSIGN_NEW_X_V2,
+    hint_code::FAST_EC_ADD_ASSIGN_NEW_X_V3,
+    hint_code::FAST_EC_ADD_ASSIGN_NEW_Y,
+    hint_code::EC_MUL_INNER,
+    hint_code::RELOCATE_SEGMENT,
+    hint_code::TEMPORARY_ARRAY,
+    hint_code::VERIFY_ECDSA_SIGNATURE,
+    hint_code::SPLIT_OUTPUT_0,
+    hint_code::SPLIT_OUTPUT_1,
+    hint_code::SPLIT_INPUT_3,
+    hint_code::SPLIT_INPUT_6,
+    hint_code::SPLIT_INPUT_9,
+    hint_code::SPLIT_INPUT_12,
+    hint_code::SPLIT_INPUT_15,
+    hint_code::SPLIT_N_BYTES,
+    hint_code::SPLIT_OUTPUT_MID_LOW_HIGH,
+    hint_code::NONDET_N_GREATER_THAN_10,
+    hint_code::NONDET_N_GREATER_THAN_2,
+    hint_code::NONDET_ELEMENTS_OVER_TEN,
+    hint_code::NONDET_ELEMENTS_OVER_TWO,
+    hint_code::RANDOM_EC_POINT,
+    hint_code::CHAINED_EC_OP_RANDOM_EC_POINT,
+    hint_code::RECOVER_Y,
+    hint_code::PACK_MODN_DIV_MODN,
+    hint_code::XS_SAFE_DIV,
+    hint_code::UINT384_UNSIGNED_DIV_REM,
+    hint_code::UINT384_SPLIT_128,
+    hint_code::ADD_NO_UINT384_CHECK,
+    hint_code::UINT384_SQRT,
+    hint_code::UNSIGNED_DIV_REM_UINT768_BY_UINT384,
+    hint_code::UNSIGNED_DIV_REM_UINT768_BY_UINT384_STRIPPED,
+    hint_code::SUB_REDUCED_A_AND_REDUCED_B,
+    hint_code::UINT384_GET_SQUARE_ROOT,
+    hint_code::UINT256_GET_SQUARE_ROOT,
+    hint_code::UINT384_SIGNED_NN,
+    hint_code::UINT384_DIV,
+    hint_code::UINT256_MUL_DIV_MOD,
+    hint_code::IMPORT_SECP256R1_ALPHA,
+    hint_code::IMPORT_SECP256R1_N,
+    hint_code::UINT512_UNSIGNED_DIV_REM,
+    hint_code::HI_MAX_BITLEN,
+    hint_code::QUAD_BIT,
+    hint_code::INV_MOD_P_UINT256,
+    hint_code::INV_MOD_P_UINT512,
+    hint_code::DI_BIT,
+    hint_code::EXAMPLE_BLAKE2S_COMPRESS,
+    hint_code::EC_RECOVER_DIV_MOD_N_PACKED,
+    hint_code::EC_RECOVER_SUB_A_B,
+    hint_code::A_B_BITAND_1,
+    hint_code::ASSERT_LE_FELT_V_0_6,
+    hint_code::ASSERT_LE_FELT_V_0_8,
+    hint_code::EC_RECOVER_PRODUCT_MOD,
+    hint_code::EC_RECOVER_PRODUCT_DIV_M,
+    hint_code::SPLIT_XX,
+    hint_code::RUN_P_CIRCUIT,
+    hint_code::RUN_P_CIRCUIT_WITH_LARGE_BATCH_SIZE,
+    hint_code::EXCESS_BALANCE,
+];
+
+/// Whether the Cairo VM builtin hint processor implements `hint_code`.
+pub(crate) fn is_cairo_vm_hint(hint_code: &str) -> bool {
+    CAIRO_VM_HINT_CODES.contains(&hint_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::add_segment(hint_code::ADD_SEGMENT, true)]
+    #[case::is_le_felt(hint_code::IS_LE_FELT, true)]
+    #[case::test_utils_hint("print(ids.x)", false)]
+    #[case::unknown_hint("ids.x = custom_hint()", false)]
+    fn test_is_cairo_vm_hint(#[case] hint_code: &str, #[case] expected: bool) {
+        assert_eq!(is_cairo_vm_hint(hint_code), expected);
+    }
+}
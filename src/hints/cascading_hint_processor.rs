@@ -0,0 +1,361 @@
+use std::any::Any;
+use std::collections::HashMap;
+
+use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintProcessorData;
+use cairo_vm::hint_processor::hint_processor_definition::{
+    HintExtension, HintProcessor, HintProcessorLogic, HintReference,
+};
+use cairo_vm::serde::deserialize_program::ApTracking;
+use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+use cairo_vm::vm::runners::cairo_runner::{ResourceTracker, RunResources};
+use cairo_vm::vm::vm_core::VirtualMachine;
+use cairo_vm::Felt252;
+
+/// Hint data compiled by a layer of a `CascadingHintProcessor`.
+struct CascadedHintData {
+    hint_code: String,
+    /// Index of the layer that compiled the hint.
+    layer: usize,
+    hint_data: Box<dyn Any>,
+}
+
+/// Code of a hint compiled by a `CascadingHintProcessor` or to `HintProcessorData`.
+pub(crate) fn get_hint_code(hint_data: &dyn Any) -> Option<&str> {
+    match hint_data.downcast_ref::<CascadedHintData>() {
+        Some(cascaded_hint_data) => Some(&cascaded_hint_data.hint_code),
+        None => hint_data
+            .downcast_ref::<HintProcessorData>()
+            .map(|hint_data| hint_data.code.as_str()),
+    }
+}
+
+fn is_unknown_hint(error: &VirtualMachineError) -> bool {
+    match error {
+        VirtualMachineError::Hint(hint_error) => matches!(hint_error.1, HintError::UnknownHint(_)),
+        _ => false,
+    }
+}
+
+/// Chains hint processors: a hint is executed by the first layer that implements it.
+///
+/// A hint is compiled by the first layer whose `compile_hint` does not fail with
+/// `HintError::UnknownHint`, and that layer is recorded in the compiled hint. The hint is executed
+/// from that layer on: the next layers get the same hint data until one does not return
+/// `HintError::UnknownHint`. Layers that compile any hint, like `BuiltinHintProcessor`, therefore
+/// hide the compilation of the layers after them.
+///
+/// Hints compiled by a layer instead of the cascade (ex: task hints compiled by
+/// `MinimalBootloaderHintProcessor`) are passed as they are to every layer.
+#[derive(Default)]
+pub struct CascadingHintProcessor<'a> {
+    layers: Vec<&'a mut dyn HintProcessor>,
+    /// Unlimited resources, reported when no layer tracks them.
+    run_resources: RunResources,
+}
+
+impl<'a> CascadingHintProcessor<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a layer, tried after the ones already added.
+    pub fn add_processor(&mut self, hint_processor: &'a mut dyn HintProcessor) {
+        self.layers.push(hint_processor);
+    }
+
+    /// Compiles a hint with the first of `layers` that knows it, for hint processors that compile
+    /// hints without mutable access to their layers.
+    pub(crate) fn compile_in_layers<'l>(
+        layers: impl IntoIterator<Item = &'l dyn HintProcessor>,
+        hint_code: &str,
+        ap_tracking_data: &ApTracking,
+        reference_ids: &HashMap<String, usize>,
+        references: &[HintReference],
+    ) -> Result<Box<dyn Any>, VirtualMachineError> {
+        for (layer_index, layer) in layers.into_iter().enumerate() {
+            match layer.compile_hint(hint_code, ap_tracking_data, reference_ids, references) {
+                Ok(hint_data) => {
+                    return Ok(Box::new(CascadedHintData {
+                        hint_code: hint_code.to_string(),
+                        layer: layer_index,
+                        hint_data,
+                    }))
+                }
+                Err(error) if is_unknown_hint(&error) => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        Err(VirtualMachineError::Hint(Box::new((
+            0,
+            HintError::UnknownHint(hint_code.to_string().into_boxed_str()),
+        ))))
+    }
+}
+
+impl HintProcessorLogic for CascadingHintProcessor<'_> {
+    fn execute_hint(
+        &mut self,
+        _vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        _hint_data: &Box<dyn Any>,
+        _constants: &HashMap<String, Felt252>,
+    ) -> Result<(), HintError> {
+        // This method will never be called, but must be defined for `HintProcessorLogic`.
+        Err(HintError::WrongHintData)
+    }
+
+    fn compile_hint(
+        &self,
+        hint_code: &str,
+        ap_tracking_data: &ApTracking,
+        reference_ids: &HashMap<String, usize>,
+        references: &[HintReference],
+    ) -> Result<Box<dyn Any>, VirtualMachineError> {
+        Self::compile_in_layers(
+            self.layers
+                .iter()
+                .map(|layer| &**layer as &dyn HintProcessor),
+            hint_code,
+            ap_tracking_data,
+            reference_ids,
+            references,
+        )
+    }
+
+    fn execute_hint_extensive(
+        &mut self,
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data: &Box<dyn Any>,
+        constants: &HashMap<String, Felt252>,
+    ) -> Result<HintExtension, HintError> {
+        let (first_layer, hint_data) = match hint_data.downcast_ref::<CascadedHintData>() {
+            Some(cascaded_hint_data) => (cascaded_hint_data.layer, &cascaded_hint_data.hint_data),
+            None => (0, hint_data),
+        };
+
+        for layer in self.layers.iter_mut().skip(first_layer) {
+            match layer.execute_hint_extensive(vm, exec_scopes, hint_data, constants) {
+                Err(HintError::UnknownHint(_)) => {}
+                result => return result,
+            }
+        }
+
+        Err(HintError::UnknownHint(
+            get_hint_code(&**hint_data)
+                .unwrap_or("<unknown hint data>")
+                .to_string()
+                .into_boxed_str(),
+        ))
+    }
+}
+
+/// Steps are reported to every layer, the resources are the ones of the first layer that tracks
+/// them, or unlimited ones if none does.
+impl ResourceTracker for CascadingHintProcessor<'_> {
+    fn consumed(&self) -> bool {
+        self.layers.iter().any(|layer| layer.consumed())
+    }
+
+    fn consume_step(&mut self) {
+        for layer in self.layers.iter_mut() {
+            layer.consume_step();
+        }
+    }
+
+    fn get_n_steps(&self) -> Option<usize> {
+        self.layers.iter().find_map(|layer| layer.get_n_steps())
+    }
+
+    fn run_resources(&self) -> &RunResources {
+        self.layers
+            .iter()
+            .find(|layer| layer.get_n_steps().is_some())
+            .map_or(&self.run_resources, |layer| layer.run_resources())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use assert_matches::assert_matches;
+    use cairo_vm::any_box;
+    use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::{
+        BuiltinHintProcessor, HintFunc,
+    };
+
+    use super::*;
+    use crate::hints::codes::BOOTLOADER_PREPARE_SIMPLE_BOOTLOADER_INPUT;
+    use crate::{vm, MinimalBootloaderHintProcessor};
+
+    const CUSTOM_HINT: &str = "ids.x = 1";
+
+    /// Implements no hint, counts the hints it is asked to compile and execute.
+    #[derive(Default)]
+    struct CountingLayer {
+        /// Error returned when compiling any hint, compiles every hint if `None`.
+        compile_error: Option<fn(&str) -> VirtualMachineError>,
+        compilations: Cell<usize>,
+        executions: usize,
+    }
+
+    impl CountingLayer {
+        fn failing_with(compile_error: fn(&str) -> VirtualMachineError) -> Self {
+            Self {
+                compile_error: Some(compile_error),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl HintProcessorLogic for CountingLayer {
+        fn execute_hint(
+            &mut self,
+            _vm: &mut VirtualMachine,
+            _exec_scopes: &mut ExecutionScopes,
+            hint_data: &Box<dyn Any>,
+            _constants: &HashMap<String, Felt252>,
+        ) -> Result<(), HintError> {
+            self.executions += 1;
+            let hint_code = get_hint_code(&**hint_data).ok_or(HintError::WrongHintData)?;
+            Err(HintError::UnknownHint(hint_code.into()))
+        }
+
+        fn compile_hint(
+            &self,
+            hint_code: &str,
+            _ap_tracking_data: &ApTracking,
+            _reference_ids: &HashMap<String, usize>,
+            _references: &[HintReference],
+        ) -> Result<Box<dyn Any>, VirtualMachineError> {
+            self.compilations.set(self.compilations.get() + 1);
+            match self.compile_error {
+                Some(compile_error) => Err(compile_error(hint_code)),
+                None => Ok(any_box!(HintProcessorData::new_default(
+                    hint_code.to_string(),
+                    HashMap::new()
+                ))),
+            }
+        }
+    }
+
+    impl ResourceTracker for CountingLayer {}
+
+    fn unknown_hint(hint_code: &str) -> VirtualMachineError {
+        VirtualMachineError::Hint(Box::new((0, HintError::UnknownHint(hint_code.into()))))
+    }
+
+    fn invalid_reference(_hint_code: &str) -> VirtualMachineError {
+        VirtualMachineError::CompileHintFail("invalid reference".into())
+    }
+
+    fn count_calls_hint() -> HintFunc {
+        HintFunc(Box::new(
+            |_vm, exec_scopes, _ids_data, _ap_tracking, _constants| {
+                let calls = exec_scopes.get::<usize>("calls").unwrap_or(0);
+                exec_scopes.insert_value("calls", calls + 1);
+                Ok(())
+            },
+        ))
+    }
+
+    fn custom_hint_processor() -> BuiltinHintProcessor {
+        let mut builtin_hint_processor = BuiltinHintProcessor::new_empty();
+        builtin_hint_processor
+            .extra_hints
+            .insert(CUSTOM_HINT.to_string(), Rc::new(count_calls_hint()));
+        builtin_hint_processor
+    }
+
+    fn compile_and_execute(
+        hint_processor: &mut CascadingHintProcessor,
+        hint_code: &str,
+    ) -> (Result<HintExtension, HintError>, ExecutionScopes) {
+        let mut vm = vm!();
+        let mut exec_scopes = ExecutionScopes::new();
+        let hint_data = hint_processor
+            .compile_hint(hint_code, &ApTracking::new(), &HashMap::new(), &[])
+            .expect("Failed to compile hint");
+        let result = hint_processor.execute_hint_extensive(
+            &mut vm,
+            &mut exec_scopes,
+            &hint_data,
+            &HashMap::new(),
+        );
+        (result, exec_scopes)
+    }
+
+    #[test]
+    fn test_cascading_hint_processor() {
+        let mut bootloader_hint_processor = MinimalBootloaderHintProcessor::new();
+        let mut builtin_hint_processor = custom_hint_processor();
+
+        let mut hint_processor = CascadingHintProcessor::new();
+        hint_processor.add_processor(&mut bootloader_hint_processor);
+        hint_processor.add_processor(&mut builtin_hint_processor);
+
+        let (result, exec_scopes) = compile_and_execute(&mut hint_processor, CUSTOM_HINT);
+        result.expect("Custom hint failed");
+        assert_eq!(exec_scopes.get::<usize>("calls").unwrap(), 1);
+
+        // Bootloader hints are implemented by the first layer. This one fails as there is no
+        // bootloader input, which is not an unknown hint.
+        let (result, _) = compile_and_execute(
+            &mut hint_processor,
+            BOOTLOADER_PREPARE_SIMPLE_BOOTLOADER_INPUT,
+        );
+        assert_matches!(result, Err(HintError::VariableNotInScopeError(_)));
+
+        let (result, _) = compile_and_execute(&mut hint_processor, "unknown_hint()");
+        assert_matches!(result, Err(HintError::UnknownHint(_)));
+    }
+
+    #[test]
+    fn test_hint_is_executed_from_the_layer_that_compiled_it() {
+        let mut rejecting_layer = CountingLayer::failing_with(unknown_hint);
+        let mut builtin_hint_processor = custom_hint_processor();
+        let mut last_layer = CountingLayer::default();
+
+        let mut hint_processor = CascadingHintProcessor::new();
+        hint_processor.add_processor(&mut rejecting_layer);
+        hint_processor.add_processor(&mut builtin_hint_processor);
+        hint_processor.add_processor(&mut last_layer);
+        for _ in 0..2 {
+            let (result, _) = compile_and_execute(&mut hint_processor, CUSTOM_HINT);
+            result.expect("Custom hint failed");
+        }
+
+        // The builtin hint processor compiled the hint: the layer before it is not asked to
+        // execute it, and the layer after it is not asked to compile it.
+        assert_eq!(rejecting_layer.compilations.get(), 2);
+        assert_eq!(rejecting_layer.executions, 0);
+        assert_eq!(last_layer.compilations.get(), 0);
+        assert_eq!(last_layer.executions, 0);
+    }
+
+    #[test]
+    fn test_compile_error_is_returned() {
+        let mut failing_layer = CountingLayer::failing_with(invalid_reference);
+        let mut last_layer = CountingLayer::default();
+
+        let mut hint_processor = CascadingHintProcessor::new();
+        hint_processor.add_processor(&mut failing_layer);
+        hint_processor.add_processor(&mut last_layer);
+        let result =
+            hint_processor.compile_hint(CUSTOM_HINT, &ApTracking::new(), &HashMap::new(), &[]);
+        assert_matches!(result, Err(VirtualMachineError::CompileHintFail(_)));
+
+        assert_eq!(last_layer.compilations.get(), 0);
+    }
+
+    #[test]
+    fn test_run_resources_without_layers() {
+        let hint_processor = CascadingHintProcessor::new();
+        assert_eq!(hint_processor.run_resources(), &RunResources::default());
+    }
+}
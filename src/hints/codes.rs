@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
 pub const BOOTLOADER_PREPARE_SIMPLE_BOOTLOADER_OUTPUT_SEGMENT: &str =
     "from starkware.cairo.bootloaders.bootloader.objects import BootloaderInput
 bootloader_input = BootloaderInput.Schema().load(program_input)
@@ -221,3 +224,371 @@ ids.select_builtin = int(
   n_selected_builtins > 0 and memory[ids.selected_encodings] == memory[ids.all_encodings])
 if ids.select_builtin:
   n_selected_builtins = n_selected_builtins - 1";
+
+/// A bootloader hint, independently of the cairo-lang version its code comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BootloaderHint {
+    PrepareSimpleBootloaderOutputSegment,
+    PrepareSimpleBootloaderInput,
+    RestoreBootloaderOutput,
+    LoadBootloaderConfig,
+    EnterPackedOutputScope,
+    ImportPackedOutputSchemas,
+    IsPlainPackedOutput,
+    SaveOutputPointer,
+    SavePackedOutputs,
+    ComputeFactTopologies,
+    GuessPreImageOfSubtasksOutputHash,
+    SetPackedOutputToSubtasks,
+    AssertIsCompositePackedOutput,
+    PrepareTaskRangeChecks,
+    SetTasksVariable,
+    DivideNumBy2,
+    SetCurrentTask,
+    SetApToZero,
+    SetApToZeroOrOne,
+    AllocateProgramDataSegment,
+    LoadProgram,
+    ValidateHash,
+    AssertProgramAddress,
+    CallTask,
+    WriteReturnBuiltins,
+    AppendFactTopologies,
+    ExitTaskScope,
+    SelectBuiltinsEnterScope,
+    SelectBuiltin,
+}
+
+/// Hint codes of the bootloader of one cairo-lang version.
+pub struct HintCodeTable {
+    pub version: &'static str,
+    pub hint_codes: &'static [(BootloaderHint, &'static str)],
+}
+
+pub const HINT_CODES_V0_13_0: HintCodeTable = HintCodeTable {
+    version: "0.13.0",
+    hint_codes: &[
+        (
+            BootloaderHint::PrepareSimpleBootloaderOutputSegment,
+            BOOTLOADER_PREPARE_SIMPLE_BOOTLOADER_OUTPUT_SEGMENT,
+        ),
+        (
+            BootloaderHint::PrepareSimpleBootloaderInput,
+            BOOTLOADER_PREPARE_SIMPLE_BOOTLOADER_INPUT,
+        ),
+        (
+            BootloaderHint::RestoreBootloaderOutput,
+            BOOTLOADER_RESTORE_BOOTLOADER_OUTPUT,
+        ),
+        (
+            BootloaderHint::LoadBootloaderConfig,
+            BOOTLOADER_LOAD_BOOTLOADER_CONFIG,
+        ),
+        (
+            BootloaderHint::EnterPackedOutputScope,
+            BOOTLOADER_ENTER_PACKED_OUTPUT_SCOPE,
+        ),
+        (
+            BootloaderHint::ImportPackedOutputSchemas,
+            BOOTLOADER_IMPORT_PACKED_OUTPUT_SCHEMAS,
+        ),
+        (
+            BootloaderHint::IsPlainPackedOutput,
+            BOOTLOADER_IS_PLAIN_PACKED_OUTPUT,
+        ),
+        (
+            BootloaderHint::SaveOutputPointer,
+            BOOTLOADER_SAVE_OUTPUT_POINTER,
+        ),
+        (
+            BootloaderHint::SavePackedOutputs,
+            BOOTLOADER_SAVE_PACKED_OUTPUTS,
+        ),
+        (
+            BootloaderHint::ComputeFactTopologies,
+            BOOTLOADER_COMPUTE_FACT_TOPOLOGIES,
+        ),
+        (
+            BootloaderHint::GuessPreImageOfSubtasksOutputHash,
+            BOOTLOADER_GUESS_PRE_IMAGE_OF_SUBTASKS_OUTPUT_HASH,
+        ),
+        (
+            BootloaderHint::SetPackedOutputToSubtasks,
+            BOOTLOADER_SET_PACKED_OUTPUT_TO_SUBTASKS,
+        ),
+        (
+            BootloaderHint::AssertIsCompositePackedOutput,
+            BOOTLOADER_ASSERT_IS_COMPOSITE_PACKED_OUTPUT,
+        ),
+        (
+            BootloaderHint::PrepareTaskRangeChecks,
+            SIMPLE_BOOTLOADER_PREPARE_TASK_RANGE_CHECKS,
+        ),
+        (
+            BootloaderHint::SetTasksVariable,
+            SIMPLE_BOOTLOADER_SET_TASKS_VARIABLE,
+        ),
+        (
+            BootloaderHint::DivideNumBy2,
+            SIMPLE_BOOTLOADER_DIVIDE_NUM_BY_2,
+        ),
+        (
+            BootloaderHint::SetCurrentTask,
+            SIMPLE_BOOTLOADER_SET_CURRENT_TASK,
+        ),
+        (BootloaderHint::SetApToZero, SIMPLE_BOOTLOADER_ZERO_V0_13_0),
+        (
+            BootloaderHint::AllocateProgramDataSegment,
+            EXECUTE_TASK_ALLOCATE_PROGRAM_DATA_SEGMENT,
+        ),
+        (BootloaderHint::LoadProgram, EXECUTE_TASK_LOAD_PROGRAM),
+        (
+            BootloaderHint::ValidateHash,
+            EXECUTE_TASK_VALIDATE_HASH_V0_13_0,
+        ),
+        (
+            BootloaderHint::AssertProgramAddress,
+            EXECUTE_TASK_ASSERT_PROGRAM_ADDRESS,
+        ),
+        (BootloaderHint::CallTask, EXECUTE_TASK_CALL_TASK),
+        (
+            BootloaderHint::WriteReturnBuiltins,
+            EXECUTE_TASK_WRITE_RETURN_BUILTINS,
+        ),
+        (
+            BootloaderHint::AppendFactTopologies,
+            EXECUTE_TASK_APPEND_FACT_TOPOLOGIES,
+        ),
+        (BootloaderHint::ExitTaskScope, EXECUTE_TASK_EXIT_SCOPE),
+        (
+            BootloaderHint::SelectBuiltinsEnterScope,
+            SELECT_BUILTINS_ENTER_SCOPE,
+        ),
+        (
+            BootloaderHint::SelectBuiltin,
+            INNER_SELECT_BUILTINS_SELECT_BUILTIN,
+        ),
+    ],
+};
+
+pub const HINT_CODES_V0_13_1: HintCodeTable = HintCodeTable {
+    version: "0.13.1",
+    hint_codes: &[
+        (
+            BootloaderHint::PrepareSimpleBootloaderOutputSegment,
+            BOOTLOADER_PREPARE_SIMPLE_BOOTLOADER_OUTPUT_SEGMENT,
+        ),
+        (
+            BootloaderHint::PrepareSimpleBootloaderInput,
+            BOOTLOADER_PREPARE_SIMPLE_BOOTLOADER_INPUT,
+        ),
+        (
+            BootloaderHint::RestoreBootloaderOutput,
+            BOOTLOADER_RESTORE_BOOTLOADER_OUTPUT,
+        ),
+        (
+            BootloaderHint::LoadBootloaderConfig,
+            BOOTLOADER_LOAD_BOOTLOADER_CONFIG,
+        ),
+        (
+            BootloaderHint::EnterPackedOutputScope,
+            BOOTLOADER_ENTER_PACKED_OUTPUT_SCOPE,
+        ),
+        (
+            BootloaderHint::ImportPackedOutputSchemas,
+            BOOTLOADER_IMPORT_PACKED_OUTPUT_SCHEMAS,
+        ),
+        (
+            BootloaderHint::IsPlainPackedOutput,
+            BOOTLOADER_IS_PLAIN_PACKED_OUTPUT,
+        ),
+        (
+            BootloaderHint::SaveOutputPointer,
+            BOOTLOADER_SAVE_OUTPUT_POINTER,
+        ),
+        (
+            BootloaderHint::SavePackedOutputs,
+            BOOTLOADER_SAVE_PACKED_OUTPUTS,
+        ),
+        (
+            BootloaderHint::ComputeFactTopologies,
+            BOOTLOADER_COMPUTE_FACT_TOPOLOGIES,
+        ),
+        (
+            BootloaderHint::GuessPreImageOfSubtasksOutputHash,
+            BOOTLOADER_GUESS_PRE_IMAGE_OF_SUBTASKS_OUTPUT_HASH,
+        ),
+        (
+            BootloaderHint::SetPackedOutputToSubtasks,
+            BOOTLOADER_SET_PACKED_OUTPUT_TO_SUBTASKS,
+        ),
+        (
+            BootloaderHint::AssertIsCompositePackedOutput,
+            BOOTLOADER_ASSERT_IS_COMPOSITE_PACKED_OUTPUT,
+        ),
+        (
+            BootloaderHint::PrepareTaskRangeChecks,
+            SIMPLE_BOOTLOADER_PREPARE_TASK_RANGE_CHECKS,
+        ),
+        (
+            BootloaderHint::SetTasksVariable,
+            SIMPLE_BOOTLOADER_SET_TASKS_VARIABLE,
+        ),
+        (
+            BootloaderHint::DivideNumBy2,
+            SIMPLE_BOOTLOADER_DIVIDE_NUM_BY_2,
+        ),
+        (
+            BootloaderHint::SetCurrentTask,
+            SIMPLE_BOOTLOADER_SET_CURRENT_TASK,
+        ),
+        (
+            BootloaderHint::SetApToZeroOrOne,
+            SIMPLE_BOOTLOADER_ZERO_V0_13_1,
+        ),
+        (
+            BootloaderHint::AllocateProgramDataSegment,
+            EXECUTE_TASK_ALLOCATE_PROGRAM_DATA_SEGMENT,
+        ),
+        (BootloaderHint::LoadProgram, EXECUTE_TASK_LOAD_PROGRAM),
+        (
+            BootloaderHint::ValidateHash,
+            EXECUTE_TASK_VALIDATE_HASH_V0_13_1,
+        ),
+        (
+            BootloaderHint::AssertProgramAddress,
+            EXECUTE_TASK_ASSERT_PROGRAM_ADDRESS,
+        ),
+        (BootloaderHint::CallTask, EXECUTE_TASK_CALL_TASK),
+        (
+            BootloaderHint::WriteReturnBuiltins,
+            EXECUTE_TASK_WRITE_RETURN_BUILTINS,
+        ),
+        (
+            BootloaderHint::AppendFactTopologies,
+            EXECUTE_TASK_APPEND_FACT_TOPOLOGIES,
+        ),
+        (BootloaderHint::ExitTaskScope, EXECUTE_TASK_EXIT_SCOPE),
+        (
+            BootloaderHint::SelectBuiltinsEnterScope,
+            SELECT_BUILTINS_ENTER_SCOPE,
+        ),
+        (
+            BootloaderHint::SelectBuiltin,
+            INNER_SELECT_BUILTINS_SELECT_BUILTIN,
+        ),
+    ],
+};
+
+/// All the supported versions. A hint code may appear in several tables, but always for the
+/// same hint.
+pub const HINT_CODE_TABLES: &[HintCodeTable] = &[HINT_CODES_V0_13_0, HINT_CODES_V0_13_1];
+
+/// Returns the bootloader hint implemented by `hint_code`, in any supported version.
+pub fn get_bootloader_hint(hint_code: &str) -> Option<BootloaderHint> {
+    static HINTS: OnceLock<HashMap<&'static str, BootloaderHint>> = OnceLock::new();
+    HINTS
+        .get_or_init(|| {
+            HINT_CODE_TABLES
+                .iter()
+                .flat_map(|table| table.hint_codes.iter())
+                .map(|(hint, hint_code)| (*hint_code, *hint))
+                .collect()
+        })
+        .get(hint_code)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::path::Path;
+
+    use cairo_vm::types::program::Program;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::bootloaders::load_bootloader;
+    use crate::run::{run_bootloader_with_hint_processor, RunOptions};
+    use crate::{CairoPieBytes, TaskSpec};
+
+    #[rstest]
+    #[case::v0_13_0(HINT_CODES_V0_13_0)]
+    #[case::v0_13_1(HINT_CODES_V0_13_1)]
+    fn test_hint_code_table(#[case] table: HintCodeTable) {
+        let hints: HashSet<BootloaderHint> =
+            table.hint_codes.iter().map(|(hint, _)| *hint).collect();
+        assert_eq!(hints.len(), table.hint_codes.len(), "{}", table.version);
+
+        for (hint, hint_code) in table.hint_codes {
+            assert_eq!(
+                get_bootloader_hint(hint_code),
+                Some(*hint),
+                "{}",
+                table.version
+            );
+        }
+    }
+
+    /// Runs the bootloader of each version with a hint code table, compiled by
+    /// `make compile-tagged-bootloaders`, on a PIE, after checking that the bootloader uses every
+    /// hint of the table.
+    #[test]
+    fn test_tagged_bootloaders() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/tagged-bootloaders");
+        for table in HINT_CODE_TABLES {
+            let tag = format!("v{}", table.version);
+            let path = dir.join(format!("{tag}.json"));
+            assert!(
+                path.is_file(),
+                "Missing {}, run `make compile-tagged-bootloaders`",
+                path.display()
+            );
+
+            let bootloader = Program::from_file(&path, Some("main")).unwrap();
+            let hint_codes: HashSet<&str> = bootloader
+                .get_hints()
+                .iter()
+                .map(|hint| hint.code.as_str())
+                .collect();
+            for (hint, hint_code) in table.hint_codes {
+                assert!(
+                    hint_codes.contains(hint_code),
+                    "{tag} does not use the {hint:?} hint code of its table"
+                );
+            }
+
+            let tasks = vec![TaskSpec::CairoPieBytes(CairoPieBytes::new(
+                include_bytes!("../../examples/fibonacci_with_output.zip").to_vec(),
+                false,
+            ))];
+            let options = RunOptions::default();
+            run_bootloader_with_hint_processor(
+                &bootloader,
+                tasks,
+                &options,
+                &mut options.hint_processor(),
+            )
+            .unwrap_or_else(|e| panic!("Failed to run the {tag} bootloader: {e}"));
+        }
+    }
+
+    #[test]
+    fn test_bundled_bootloader_is_v0_13_1() {
+        let bootloader = load_bootloader().unwrap();
+        let used_hints: HashSet<BootloaderHint> = bootloader
+            .get_hints()
+            .iter()
+            .filter_map(|hint| get_bootloader_hint(&hint.code))
+            .collect();
+        let v0_13_1_hints: HashSet<BootloaderHint> = HINT_CODES_V0_13_1
+            .hint_codes
+            .iter()
+            .map(|(hint, _)| *hint)
+            // The Stwo bootloader does not include the hash validation hint.
+            .filter(|hint| *hint != BootloaderHint::ValidateHash)
+            .collect();
+
+        assert_eq!(used_hints, v0_13_1_hints);
+    }
+}
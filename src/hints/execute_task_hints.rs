@@ -1,13 +1,15 @@
 use std::any::Any;
 use std::collections::HashMap;
+use std::rc::Rc;
 
+use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintProcessorData;
 use cairo_vm::hint_processor::builtin_hint_processor::hint_utils::{
     get_ptr_from_var_name, get_relocatable_from_var_name, insert_value_from_var_name,
 };
 use cairo_vm::hint_processor::hint_processor_definition::{
     ExtensionData, HintExtension, HintProcessor, HintReference,
 };
-use cairo_vm::serde::deserialize_program::{ApTracking, Identifier};
+use cairo_vm::serde::deserialize_program::{ApTracking, HintParams, Identifier};
 use cairo_vm::types::builtin_name::BuiltinName;
 use cairo_vm::types::exec_scope::ExecutionScopes;
 use cairo_vm::types::program::Program;
@@ -15,33 +17,36 @@ use cairo_vm::types::relocatable::Relocatable;
 use cairo_vm::vm::errors::hint_errors::HintError;
 use cairo_vm::vm::errors::memory_errors::MemoryError;
 use cairo_vm::vm::runners::builtin_runner::{OutputBuiltinRunner, OutputBuiltinState};
-use cairo_vm::vm::runners::cairo_pie::{CairoPie, StrippedProgram};
+use cairo_vm::vm::runners::cairo_pie::StrippedProgram;
 use cairo_vm::vm::vm_core::VirtualMachine;
 use cairo_vm::{any_box, Felt252};
 use starknet_crypto::FieldElement;
 
 use crate::hints::fact_topologies::{get_task_fact_topology, FactTopology};
 use crate::hints::load_cairo_pie::load_cairo_pie;
+use crate::hints::pie_resources::{
+    check_cairo_pie_resources, enforce_pie_resources_check, ObservedPieResources,
+    PieResourcesCheck, PieResourcesReport,
+};
 use crate::hints::program_hash::compute_program_hash_chain;
 use crate::hints::program_loader::ProgramLoader;
 use crate::hints::types::{BootloaderVersion, ProgramIdentifiers, Task};
 use crate::hints::vars;
-use crate::TaskSpec;
+use crate::tasks::BootloaderTaskError;
+use crate::{Error, TaskSpec};
 
-use super::types::{CairoPieTask, RunProgramTask};
+use super::types::{Cairo1ProgramTask, CairoPieTask, RunProgramTask};
 
 fn get_stripped_program_from_task(task: &Box<dyn Task>) -> Result<StrippedProgram, HintError> {
-    task.get_program()
-        .map_err(|e| HintError::CustomHint(e.to_string().into_boxed_str()))
-        .and_then(|p| {
-            p.get_stripped_program()
-                .map_err(|e| HintError::CustomHint(e.to_string().into_boxed_str()))
-        })
+    get_program_from_task(task).and_then(|p| {
+        p.get_stripped_program()
+            .map_err(|e| Error::from(BootloaderTaskError::from(e)).into())
+    })
 }
 
 fn get_program_from_task(task: &Box<dyn Task>) -> Result<Program, HintError> {
     task.get_program()
-        .map_err(|e| HintError::CustomHint(e.to_string().into_boxed_str()))
+        .map_err(|e| Error::from(BootloaderTaskError::from(e)).into())
 }
 
 fn get_task_from_exec_scopes(exec_scopes: &ExecutionScopes) -> Result<Box<dyn Task>, HintError> {
@@ -53,7 +58,7 @@ fn get_task_from_exec_scopes(exec_scopes: &ExecutionScopes) -> Result<Box<dyn Ta
         .unwrap();
     let task = task_spec
         .load_task()
-        .map_err(|e| HintError::CustomHint(e.to_string().into_boxed_str()))?;
+        .map_err(|e| Error::from(BootloaderTaskError::from(e)))?;
     Ok(task)
 }
 
@@ -166,6 +171,17 @@ pub fn append_fact_topologies(
     Ok(HashMap::new())
 }
 
+/// Reads the hash of the task program, written by the bootloader to `[ids.output_ptr + 1]` once
+/// it hashed the program loaded in memory.
+pub(crate) fn get_task_program_hash(
+    vm: &VirtualMachine,
+    ids_data: &HashMap<String, HintReference>,
+    ap_tracking: &ApTracking,
+) -> Result<Felt252, HintError> {
+    let output_ptr = get_ptr_from_var_name("output_ptr", vm, ids_data, ap_tracking)?;
+    Ok(vm.get_integer((output_ptr + 1)?)?.into_owned())
+}
+
 /// Implements
 /// # Validate hash.
 /// from starkware.cairo.bootloaders.hash_program import compute_program_hash_chain
@@ -182,24 +198,20 @@ pub fn validate_hash(
     let task = get_task_from_exec_scopes(exec_scopes)?;
     let program = get_stripped_program_from_task(&task)?;
 
-    let output_ptr = get_ptr_from_var_name("output_ptr", vm, ids_data, ap_tracking)?;
-    let program_hash_ptr = (output_ptr + 1)?;
-
-    let program_hash = vm.get_integer(program_hash_ptr)?.into_owned();
+    let program_hash = get_task_program_hash(vm, ids_data, ap_tracking)?;
 
     // Compute the hash of the program
-    let computed_program_hash = compute_program_hash_chain(&program, 0).map_err(|e| {
-        HintError::CustomHint(format!("Could not compute program hash: {e}").into_boxed_str())
-    })?;
+    let computed_program_hash = compute_program_hash_chain(&program, 0).map_err(Error::from)?;
     let computed_program_hash = field_element_to_felt(computed_program_hash);
 
     if program_hash != computed_program_hash {
-        return Err(HintError::AssertionFailed(
-            "Computed hash does not match input"
-                .to_string()
-                .into_boxed_str(),
-        ));
+        return Err(Error::ProgramHashMismatch {
+            expected: program_hash,
+            computed: computed_program_hash,
+        }
+        .into());
     }
+    exec_scopes.insert_value(vars::PROGRAM_HASH, program_hash);
 
     Ok(HashMap::new())
 }
@@ -219,32 +231,6 @@ pub const ALL_BUILTINS: [BuiltinName; 11] = [
     BuiltinName::mul_mod,
 ];
 
-fn check_cairo_pie_builtin_usage(
-    vm: &mut VirtualMachine,
-    builtin_name: &BuiltinName,
-    builtin_index: usize,
-    cairo_pie: &CairoPie,
-    return_builtins_addr: Relocatable,
-    pre_execution_builtins_addr: Relocatable,
-) -> Result<HintExtension, HintError> {
-    let return_builtin_value = vm.get_relocatable((return_builtins_addr + builtin_index)?)?;
-    let pre_execution_builtin_value =
-        vm.get_relocatable((pre_execution_builtins_addr + builtin_index)?)?;
-    let expected_builtin_size = (return_builtin_value - pre_execution_builtin_value)?;
-
-    let builtin_size = cairo_pie.metadata.builtin_segments[builtin_name].size;
-
-    if builtin_size != expected_builtin_size {
-        return Err(HintError::AssertionFailed(
-            "Builtin usage is inconsistent with the CairoPie."
-                .to_string()
-                .into_boxed_str(),
-        ));
-    }
-
-    Ok(HashMap::new())
-}
-
 /// Writes the updated builtin pointers after the program execution to the given return builtins
 /// address.
 ///
@@ -255,7 +241,6 @@ fn write_return_builtins(
     used_builtins: &[BuiltinName],
     used_builtins_addr: Relocatable,
     pre_execution_builtins_addr: Relocatable,
-    task: &Box<dyn Task>,
 ) -> Result<HintExtension, HintError> {
     let mut used_builtin_offset: usize = 0;
     for (index, builtin) in ALL_BUILTINS.iter().enumerate() {
@@ -263,17 +248,6 @@ fn write_return_builtins(
             let builtin_value = vm.get_relocatable((used_builtins_addr + used_builtin_offset)?)?;
             vm.insert_value((return_builtins_addr + index)?, builtin_value)?;
             used_builtin_offset += 1;
-
-            if let Some(cairo_pie_task) = task.as_any().downcast_ref::<CairoPieTask>() {
-                check_cairo_pie_builtin_usage(
-                    vm,
-                    builtin,
-                    index,
-                    &cairo_pie_task.cairo_pie,
-                    return_builtins_addr,
-                    pre_execution_builtins_addr,
-                )?;
-            }
         }
         // The builtin is unused, hence its value is the same as before calling the program.
         else {
@@ -288,6 +262,35 @@ fn write_return_builtins(
     Ok(HashMap::new())
 }
 
+/// Returns the size of the segment of each used builtin, computed from the builtin pointers
+/// before and after the execution of the task.
+fn get_used_builtin_sizes(
+    vm: &VirtualMachine,
+    used_builtins: &[BuiltinName],
+    return_builtins_addr: Relocatable,
+    pre_execution_builtins_addr: Relocatable,
+) -> Result<Vec<(BuiltinName, usize)>, HintError> {
+    let mut builtin_sizes = vec![];
+    for (index, builtin) in ALL_BUILTINS.iter().enumerate() {
+        if used_builtins.contains(builtin) {
+            let return_builtin_value = vm.get_relocatable((return_builtins_addr + index)?)?;
+            let pre_execution_builtin_value =
+                vm.get_relocatable((pre_execution_builtins_addr + index)?)?;
+            let builtin_size = (return_builtin_value - pre_execution_builtin_value)?;
+            builtin_sizes.push((*builtin, builtin_size));
+        }
+    }
+    Ok(builtin_sizes)
+}
+
+/// Returns the number of steps executed by the current task, if the hint processor tracks steps.
+fn get_task_n_steps(exec_scopes: &ExecutionScopes) -> Option<usize> {
+    let start_step: usize = exec_scopes.get(vars::TASK_START_STEP).ok()?;
+    let end_step: usize = exec_scopes.get(vars::TASK_END_STEP).ok()?;
+    // Do not count the `call` instruction of the bootloader.
+    end_step.checked_sub(start_step + 1)
+}
+
 /// Implements
 /// from starkware.cairo.bootloaders.simple_bootloader.utils import write_return_builtins
 ///
@@ -303,11 +306,16 @@ fn write_return_builtins(
 /// This hint looks at the builtins written by the program and merges them with the stored
 /// pre-execution values (stored in a struct named ids.pre_execution_builtin_ptrs) to
 /// create a final BuiltinData struct for the program.
+///
+/// For Cairo PIE tasks, the execution resources recorded in the PIE are also compared with the
+/// ones observed by the bootloader. The report is appended to `pie_resources_reports`.
 pub fn write_return_builtins_hint(
     vm: &mut VirtualMachine,
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
+    pie_resources_check: PieResourcesCheck,
+    pie_resources_reports: &mut Vec<PieResourcesReport>,
 ) -> Result<HintExtension, HintError> {
     let task = get_task_from_exec_scopes(exec_scopes)?;
     let n_builtins: usize = exec_scopes.get(vars::N_BUILTINS)?;
@@ -333,9 +341,26 @@ pub fn write_return_builtins_hint(
         builtins,
         used_builtins_addr,
         pre_execution_builtins_addr,
-        &task,
     )?;
 
+    if let Some(cairo_pie_task) = task.as_any().downcast_ref::<CairoPieTask>() {
+        let observed = ObservedPieResources {
+            n_steps: get_task_n_steps(exec_scopes),
+            execution_segment_address: exec_scopes.get(vars::EXECUTION_SEGMENT_ADDRESS)?,
+            builtin_sizes: get_used_builtin_sizes(
+                vm,
+                builtins,
+                return_builtins_addr,
+                pre_execution_builtins_addr,
+            )?,
+        };
+        let task_index: usize = exec_scopes.get(vars::TASK_ID)?;
+        let report =
+            check_cairo_pie_resources(vm, task_index, &cairo_pie_task.cairo_pie, &observed);
+        enforce_pie_resources_check(&report, pie_resources_check)?;
+        pie_resources_reports.push(report);
+    }
+
     // vm_enter_scope({'n_selected_builtins': n_builtins})
     let n_builtins: Box<dyn Any> = Box::new(n_builtins);
     exec_scopes.enter_scope(HashMap::from([(
@@ -418,6 +443,7 @@ Implements hint:
 */
 pub fn call_task(
     hint_processor: &mut dyn HintProcessor,
+    compiled_hints_cache: &mut CompiledHintsCache,
     vm: &mut VirtualMachine,
     exec_scopes: &mut ExecutionScopes,
     ids_data: &HashMap<String, HintReference>,
@@ -434,14 +460,21 @@ pub fn call_task(
     if let Some(run_program_task) = task.as_any().downcast_ref::<RunProgramTask>() {
         let program_input = run_program_task.program_input.clone();
         // new_task_locals['program_input'] = task.program_input
-        new_task_locals.insert("program_input".to_string(), any_box![program_input]);
+        new_task_locals.insert(vars::PROGRAM_INPUT.to_string(), any_box![program_input]);
         // new_task_locals['WITH_BOOTLOADER'] = True
-        new_task_locals.insert("WITH_BOOTLOADER".to_string(), any_box![true]);
+        new_task_locals.insert(vars::WITH_BOOTLOADER.to_string(), any_box![true]);
 
         // TODO: the content of this function is mostly useless for the Rust VM.
         //       check with SW if there is nothing of interest here.
         // vm_load_program(task.program, program_address)
-        let task_hint_extension = vm_load_program(hint_processor, exec_scopes)?;
+        let task_hint_extension =
+            vm_load_program(hint_processor, exec_scopes, compiled_hints_cache)?;
+        hint_extension.extend(task_hint_extension);
+    } else if task.as_any().downcast_ref::<Cairo1ProgramTask>().is_some() {
+        // Not supported by the Python bootloader. The hint codes of Cairo 1 programs are the hint
+        // offsets, the hints themselves are executed by `BootloaderHintProcessor`.
+        let task_hint_extension =
+            vm_load_program(hint_processor, exec_scopes, compiled_hints_cache)?;
         hint_extension.extend(task_hint_extension);
     } else if let Some(cairo_pie_task) = task.as_any().downcast_ref::<CairoPieTask>() {
         let program_address: Relocatable = exec_scopes.get("program_address")?;
@@ -459,19 +492,19 @@ pub fn call_task(
         //     task=task.cairo_pie, memory=memory, segments=segments,
         //     program_address=program_address, execution_segment_address= ap - n_builtins,
         //     builtin_runners=builtin_runners, ret_fp=fp, ret_pc=ret_pc)
+        let execution_segment_address = (vm.get_ap() - n_builtins)?;
         load_cairo_pie(
             &cairo_pie_task.cairo_pie,
             vm,
             program_address,
-            (vm.get_ap() - n_builtins)?,
+            execution_segment_address,
             vm.get_fp(),
             ret_pc,
         )
         .map_err(Into::<HintError>::into)?;
+        exec_scopes.insert_value(vars::EXECUTION_SEGMENT_ADDRESS, execution_segment_address);
     } else {
-        return Err(HintError::CustomHint(
-            "Unexpected task type".to_string().into_boxed_str(),
-        ));
+        return Err(Error::UnexpectedTaskType.into());
     }
 
     // output_runner_data = prepare_output_runner(
@@ -493,52 +526,162 @@ pub fn call_task(
     Ok(hint_extension)
 }
 
-fn vm_load_program(
-    hint_processor: &mut dyn HintProcessor,
-    exec_scopes: &mut ExecutionScopes,
-) -> Result<HashMap<Relocatable, ExtensionData>, HintError> {
-    let task_program_address: Relocatable = exec_scopes.get(vars::PROGRAM_ADDRESS).unwrap();
-    let task = get_task_from_exec_scopes(exec_scopes)?;
-    let task_program = get_program_from_task(&task)?;
+/// Compiled hints of a task program, by offset in the program.
+type ProgramHints = Vec<(usize, Vec<Box<dyn Any>>)>;
+
+/// Hints of a task program compiled by `vm_load_program`, by offset in the program.
+struct CompiledProgramHints {
+    /// Hints, hint ranges and references of the program, which are not part of the program hash:
+    /// programs with the same hash but different hints do not share their compiled hints.
+    program_hints: Vec<HintParams>,
+    hint_ranges: Vec<(usize, usize, usize)>,
+    references: Vec<HintReference>,
+    hints: Vec<(usize, Vec<HintProcessorData>)>,
+}
 
-    let mut task_program_compiled_hints = HashMap::new();
-    let task_program_hints = task_program.get_hints();
-    let task_program_hint_ranges = task_program.get_hints_ranges();
-    let task_program_references = task_program.get_references();
-    let task_program_constants = task_program.get_constants();
+impl CompiledProgramHints {
+    /// Keeps a copy of the hints of `program`. Returns `None` if the hint processor compiles hints
+    /// to something else than `HintProcessorData`, which cannot be copied.
+    fn new(program: &Program, program_hints: &ProgramHints) -> Option<Self> {
+        let hints = program_hints
+            .iter()
+            .map(|(hint_offset, hints)| {
+                let hints = hints
+                    .iter()
+                    .map(|hint| {
+                        hint.downcast_ref::<HintProcessorData>()
+                            .map(copy_hint_processor_data)
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some((*hint_offset, hints))
+            })
+            .collect::<Option<Vec<_>>>()?;
 
-    for hint_range in task_program_hint_ranges {
-        let hint_pc = hint_range.0;
-        let hint_range_indices = hint_range.1;
-        let (s, l) = hint_range_indices;
-        for idx in *s..(*s + l.get()) {
-            let hint = task_program_hints.get(idx).unwrap();
+        Some(Self {
+            program_hints: program.get_hints().to_vec(),
+            hint_ranges: get_hint_ranges(program),
+            references: program.get_references().to_vec(),
+            hints,
+        })
+    }
 
-            let new_hint_pc_segment = hint_pc.segment_index + task_program_address.segment_index;
-            let new_hint_pc_offset = hint_pc.offset + task_program_address.offset;
-            let new_hint_pc = Relocatable::from((new_hint_pc_segment, new_hint_pc_offset));
+    /// Whether these are the hints of `program`, a program with the same hash.
+    fn compiled_from(&self, program: &Program) -> bool {
+        self.program_hints.iter().eq(program.get_hints())
+            && self.hint_ranges == get_hint_ranges(program)
+            && self.references.iter().eq(program.get_references())
+    }
 
-            let compiled_hint = hint_processor.compile_hint(
+    fn program_hints(&self) -> ProgramHints {
+        self.hints
+            .iter()
+            .map(|(hint_offset, hints)| {
+                let hints = hints
+                    .iter()
+                    .map(|hint| any_box!(copy_hint_processor_data(hint)))
+                    .collect();
+                (*hint_offset, hints)
+            })
+            .collect()
+    }
+}
+
+/// Compiled task hints by program hash, so that the tasks of a batch that run the same program
+/// only compile its hints once.
+#[derive(Default)]
+pub(crate) struct CompiledHintsCache(HashMap<Felt252, Rc<CompiledProgramHints>>);
+
+impl CompiledHintsCache {
+    /// Number of programs whose hints are cached.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Offset, start and length of each range of hints of `program`, by offset.
+fn get_hint_ranges(program: &Program) -> Vec<(usize, usize, usize)> {
+    let mut hint_ranges: Vec<_> = program
+        .get_hints_ranges()
+        .iter()
+        .map(|(hint_pc, (start, length))| (hint_pc.offset, *start, length.get()))
+        .collect();
+    hint_ranges.sort_unstable();
+    hint_ranges
+}
+
+fn copy_hint_processor_data(hint_data: &HintProcessorData) -> HintProcessorData {
+    HintProcessorData {
+        code: hint_data.code.clone(),
+        ap_tracking: hint_data.ap_tracking.clone(),
+        ids_data: hint_data.ids_data.clone(),
+    }
+}
+
+fn compile_program_hints(
+    hint_processor: &dyn HintProcessor,
+    program: &Program,
+) -> Result<ProgramHints, HintError> {
+    let program_hints = program.get_hints();
+    let program_references = program.get_references();
+
+    let mut hints = vec![];
+    for (hint_pc, (start, length)) in program.get_hints_ranges() {
+        let mut compiled_hints = vec![];
+        for hint in &program_hints[*start..*start + length.get()] {
+            compiled_hints.push(hint_processor.compile_hint(
                 hint.code.as_str(),
                 &hint.flow_tracking_data.ap_tracking,
                 &hint.flow_tracking_data.reference_ids,
-                task_program_references,
-            )?;
-            task_program_compiled_hints
-                .entry(new_hint_pc)
-                .or_insert_with(ExtensionData::default)
-                .hints
-                .push(compiled_hint);
+                program_references,
+            )?);
         }
+        hints.push((hint_pc.offset, compiled_hints));
     }
 
-    task_program_compiled_hints
-        .iter_mut()
-        .for_each(|(_, extension_data)| {
-            extension_data
-                .constants
-                .extend(task_program_constants.iter().map(|(k, v)| (k.clone(), *v)));
-        });
+    Ok(hints)
+}
+
+fn vm_load_program(
+    hint_processor: &mut dyn HintProcessor,
+    exec_scopes: &mut ExecutionScopes,
+    compiled_hints_cache: &mut CompiledHintsCache,
+) -> Result<HashMap<Relocatable, ExtensionData>, HintError> {
+    let task_program_address: Relocatable = exec_scopes.get(vars::PROGRAM_ADDRESS).unwrap();
+    let task = get_task_from_exec_scopes(exec_scopes)?;
+    let task_program = get_program_from_task(&task)?;
+    // Read from the bootloader memory before calling the task.
+    let program_hash: Option<Felt252> = exec_scopes.get(vars::PROGRAM_HASH).ok();
+
+    let cached_hints = program_hash
+        .and_then(|program_hash| compiled_hints_cache.0.get(&program_hash))
+        .filter(|cached_hints| cached_hints.compiled_from(&task_program));
+    let program_hints = match cached_hints {
+        Some(cached_hints) => cached_hints.program_hints(),
+        None => {
+            let program_hints = compile_program_hints(hint_processor, &task_program)?;
+            let compiled_hints = CompiledProgramHints::new(&task_program, &program_hints);
+            if let (Some(program_hash), Some(compiled_hints)) = (program_hash, compiled_hints) {
+                compiled_hints_cache
+                    .0
+                    .insert(program_hash, Rc::new(compiled_hints));
+            }
+            program_hints
+        }
+    };
+
+    let task_program_constants = task_program.get_constants();
+    let mut task_program_compiled_hints = HashMap::new();
+    for (hint_offset, hints) in program_hints {
+        let mut extension_data = ExtensionData {
+            hints,
+            ..Default::default()
+        };
+        extension_data
+            .constants
+            .extend(task_program_constants.iter().map(|(k, v)| (k.clone(), *v)));
+        task_program_compiled_hints.insert((task_program_address + hint_offset)?, extension_data);
+    }
 
     Ok(task_program_compiled_hints)
 }
@@ -566,16 +709,16 @@ mod util {
         output_builtin: &mut OutputBuiltinRunner,
         output_ptr: Relocatable,
     ) -> Result<Option<OutputBuiltinState>, HintError> {
-        let output_state = if task.as_any().downcast_ref::<RunProgramTask>().is_some() {
+        let output_state = if task.as_any().downcast_ref::<RunProgramTask>().is_some()
+            || task.as_any().downcast_ref::<Cairo1ProgramTask>().is_some()
+        {
             let output_state = output_builtin.get_state();
             output_builtin.new_state(output_ptr.segment_index as usize, 0, true);
             Ok(Some(output_state))
         } else if task.as_any().downcast_ref::<CairoPieTask>().is_some() {
             Ok(None)
         } else {
-            Err(HintError::CustomHint(
-                "Unexpected task type".to_string().into_boxed_str(),
-            ))
+            Err(Error::UnexpectedTaskType.into())
         };
         output_state
     }
@@ -592,7 +735,7 @@ mod tests {
     use cairo_vm::types::program::Program;
     use cairo_vm::types::relocatable::MaybeRelocatable;
     use cairo_vm::vm::runners::builtin_runner::BuiltinRunner;
-    use cairo_vm::vm::runners::cairo_pie::{BuiltinAdditionalData, PublicMemoryPage};
+    use cairo_vm::vm::runners::cairo_pie::{BuiltinAdditionalData, CairoPie, PublicMemoryPage};
 
     use rstest::{fixture, rstest};
 
@@ -600,7 +743,7 @@ mod tests {
 
     use crate::{
         add_segments, define_segments, ids_data, non_continuous_ids_data, run_hint, vm,
-        BootloaderHintProcessor,
+        BootloaderHintProcessor, MinimalBootloaderHintProcessor,
     };
 
     use super::*;
@@ -729,11 +872,17 @@ mod tests {
 
         // Allocate space for pre-execution (8 felts), which mimics the `BuiltinData` struct in the
         // Bootloader's Cairo code. Our code only uses the first felt (`output` field in the struct)
-        define_segments!(vm, 2, [((1, 0), (2, 0))]);
+        // The task output starts at `output_ptr`, its program hash at `output_ptr + 1`.
+        define_segments!(
+            vm,
+            2,
+            [((1, 0), (2, 0)), ((1, 8), (1, 9)), ((1, 10), 0x1234)]
+        );
         vm.set_fp(8);
         add_segments!(vm, 1);
 
-        let ids_data = non_continuous_ids_data![(vars::PRE_EXECUTION_BUILTIN_PTRS, -8)];
+        let ids_data =
+            non_continuous_ids_data![(vars::PRE_EXECUTION_BUILTIN_PTRS, -8), ("output_ptr", 0),];
 
         let mut exec_scopes = ExecutionScopes::new();
 
@@ -767,11 +916,17 @@ mod tests {
 
         // Allocate space for pre-execution (8 felts), which mimics the `BuiltinData` struct in the
         // Bootloader's Cairo code. Our code only uses the first felt (`output` field in the struct)
-        define_segments!(vm, 2, [((1, 0), (2, 0))]);
+        // The task output starts at `output_ptr`, its program hash at `output_ptr + 1`.
+        define_segments!(
+            vm,
+            2,
+            [((1, 0), (2, 0)), ((1, 8), (1, 9)), ((1, 10), 0x1234)]
+        );
         vm.set_fp(8);
         add_segments!(vm, 1);
 
-        let ids_data = non_continuous_ids_data![(vars::PRE_EXECUTION_BUILTIN_PTRS, -8)];
+        let ids_data =
+            non_continuous_ids_data![(vars::PRE_EXECUTION_BUILTIN_PTRS, -8), ("output_ptr", 0),];
 
         let mut exec_scopes = ExecutionScopes::new();
 
@@ -792,7 +947,8 @@ mod tests {
         ));
         exec_scopes.insert_box(vars::TASK, Box::new(task));
 
-        let hint_processor = BootloaderHintProcessor::new();
+        // Task hints are compiled by the bootloader hints, as in `call_task`.
+        let hint_processor = MinimalBootloaderHintProcessor::new();
         let hint_code = "ids.fibonacci_claim_index = program_input['fibonacci_claim_index']";
         let hint = fibonacci_with_hint.get_hints().first().unwrap();
         let references = fibonacci_with_hint.get_references();
@@ -829,6 +985,84 @@ mod tests {
         assert_eq!(actual_hint.ids_data, compiled_hint.ids_data);
     }
 
+    #[rstest]
+    fn test_vm_load_program_reuses_compiled_hints(fibonacci_with_hint: Program) {
+        let mut exec_scopes = ExecutionScopes::new();
+        let task = TaskSpec::RunProgram(RunProgramTask::new(
+            fibonacci_with_hint,
+            HashMap::new(),
+            false,
+        ));
+        exec_scopes.insert_box(vars::TASK, Box::new(task));
+        // Normally set by the CallTask hint, from the bootloader memory.
+        exec_scopes.insert_value(vars::PROGRAM_HASH, Felt252::from(0x1234));
+
+        let mut hint_processor = MinimalBootloaderHintProcessor::new();
+        let mut compiled_hints_cache = CompiledHintsCache::default();
+        let mut load_program_at = |program_address: Relocatable| {
+            exec_scopes.insert_value(vars::PROGRAM_ADDRESS, program_address);
+            vm_load_program(
+                &mut hint_processor,
+                &mut exec_scopes,
+                &mut compiled_hints_cache,
+            )
+            .expect("Failed to load program hints")
+        };
+        let first_task_hints = load_program_at(Relocatable::from((2, 0)));
+        let second_task_hints = load_program_at(Relocatable::from((3, 5)));
+        assert_eq!(compiled_hints_cache.len(), 1);
+
+        // The cached hints are relocated to the address of the second program.
+        let get_hint_code = |hint_extension: &HintExtension, pc: Relocatable| {
+            hint_extension[&pc].hints[0]
+                .downcast_ref::<HintProcessorData>()
+                .unwrap()
+                .code
+                .clone()
+        };
+        assert_eq!(first_task_hints.len(), second_task_hints.len());
+        assert_eq!(
+            get_hint_code(&first_task_hints, Relocatable::from((2, 8))),
+            get_hint_code(&second_task_hints, Relocatable::from((3, 13)))
+        );
+    }
+
+    #[rstest]
+    fn test_vm_load_program_checks_cached_references(fibonacci_with_hint: Program) {
+        let program_json =
+            std::str::from_utf8(include_bytes!("../../examples/fibonacci_with_hint.json"))
+                .unwrap()
+                .replace("[cast(fp, felt*)]", "[cast(fp + (-1), felt*)]");
+        let moved_reference = Program::from_bytes(program_json.as_bytes(), Some("main")).unwrap();
+
+        let mut hint_processor = MinimalBootloaderHintProcessor::new();
+        let mut compiled_hints_cache = CompiledHintsCache::default();
+        let mut load_program = |program: Program| {
+            let mut exec_scopes = ExecutionScopes::new();
+            let task = TaskSpec::RunProgram(RunProgramTask::new(program, HashMap::new(), false));
+            exec_scopes.insert_box(vars::TASK, Box::new(task));
+            exec_scopes.insert_value(vars::PROGRAM_ADDRESS, Relocatable::from((2, 0)));
+            // Same hash: the programs only differ by a reference, which is not hashed.
+            exec_scopes.insert_value(vars::PROGRAM_HASH, Felt252::from(0x1234));
+            let hint_extension = vm_load_program(
+                &mut hint_processor,
+                &mut exec_scopes,
+                &mut compiled_hints_cache,
+            )
+            .expect("Failed to load program hints");
+            copy_hint_processor_data(
+                hint_extension[&Relocatable::from((2, 8))].hints[0]
+                    .downcast_ref::<HintProcessorData>()
+                    .unwrap(),
+            )
+        };
+
+        let first_hint = load_program(fibonacci_with_hint);
+        let second_hint = load_program(moved_reference);
+        assert_eq!(first_hint.code, second_hint.code);
+        assert_ne!(first_hint.ids_data, second_hint.ids_data);
+    }
+
     /// Creates a fake Program struct to act as a placeholder for the `BOOTLOADER_PROGRAM` variable.
     /// These other options have been considered:
     /// * a `HasIdentifiers` trait cannot be used as exec_scopes requires to cast to `Box<dyn Any>`,
@@ -906,6 +1140,7 @@ mod tests {
         // Execute it
         call_task(
             &mut hint_processor,
+            &mut CompiledHintsCache::default(),
             &mut vm,
             &mut exec_scopes,
             &ids_data,
@@ -1038,8 +1273,15 @@ mod tests {
         exec_scopes.insert_value(vars::N_BUILTINS, n_builtins);
         exec_scopes.insert_value(vars::TASK, task);
 
-        write_return_builtins_hint(&mut vm, &mut exec_scopes, &ids_data, &ap_tracking)
-            .expect("Hint failed unexpectedly");
+        write_return_builtins_hint(
+            &mut vm,
+            &mut exec_scopes,
+            &ids_data,
+            &ap_tracking,
+            PieResourcesCheck::Strict,
+            &mut vec![],
+        )
+        .expect("Hint failed unexpectedly");
 
         // Check that the return builtins were written correctly
         let return_builtins = vm
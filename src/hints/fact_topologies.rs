@@ -11,13 +11,13 @@ use cairo_vm::vm::runners::builtin_runner::{OutputBuiltinRunner, OutputBuiltinSt
 use cairo_vm::vm::runners::cairo_pie::{
     BuiltinAdditionalData, OutputBuiltinAdditionalData, Pages, PublicMemoryPage,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::hints::types::{PackedOutput, Task};
 
-use super::types::{CairoPieTask, RunProgramTask};
+use super::types::{Cairo1ProgramTask, CairoPieTask, RunProgramTask};
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FactTopology {
     #[allow(dead_code)]
     pub tree_structure: Vec<usize>,
@@ -75,9 +75,6 @@ pub enum FactTopologyError {
     #[error("Expected {0} fact topologies but got {1}")]
     WrongNumberOfFactTopologies(usize, usize),
 
-    #[error("Composite packed outputs are not supported yet")]
-    CompositePackedOutputNotSupported(PackedOutput),
-
     #[error("Could not add page to output: {0}")]
     FailedToAddOutputPage(#[from] RunnerError),
 
@@ -90,13 +87,7 @@ pub enum FactTopologyError {
 
 impl From<FactTopologyError> for HintError {
     fn from(value: FactTopologyError) -> Self {
-        match value {
-            FactTopologyError::Math(e) => HintError::Math(e),
-            FactTopologyError::WrongNumberOfFactTopologies(_, _) => {
-                HintError::AssertionFailed(value.to_string().into_boxed_str())
-            }
-            _ => HintError::CustomHint(value.to_string().into_boxed_str()),
-        }
+        crate::Error::from(value).into()
     }
 }
 
@@ -110,7 +101,7 @@ pub enum WriteFactTopologiesError {
 
 impl From<WriteFactTopologiesError> for HintError {
     fn from(value: WriteFactTopologiesError) -> Self {
-        HintError::CustomHint(value.to_string().into_boxed_str())
+        crate::Error::from(value).into()
     }
 }
 
@@ -121,7 +112,7 @@ impl From<WriteFactTopologiesError> for HintError {
 /// * `packed_outputs`: Packed outputs.
 /// * `fact_topologies`: Fact topologies.
 pub fn compute_fact_topologies<'a>(
-    packed_outputs: &Vec<PackedOutput>,
+    packed_outputs: &'a Vec<PackedOutput>,
     fact_topologies: &'a Vec<FactTopology>,
 ) -> Result<Vec<&'a FactTopology>, FactTopologyError> {
     if packed_outputs.len() != fact_topologies.len() {
@@ -138,10 +129,8 @@ pub fn compute_fact_topologies<'a>(
             PackedOutput::Plain(_) => {
                 plain_fact_topologies.push(fact_topology);
             }
-            PackedOutput::Composite(_) => {
-                return Err(FactTopologyError::CompositePackedOutputNotSupported(
-                    packed_output.clone(),
-                ));
+            PackedOutput::Composite(composite_packed_output) => {
+                plain_fact_topologies.extend(composite_packed_output.get_plain_fact_topologies()?);
             }
         }
     }
@@ -382,7 +371,9 @@ pub fn get_task_fact_topology(
     output_builtin: &mut OutputBuiltinRunner,
     output_runner_data: Option<OutputBuiltinState>,
 ) -> Result<FactTopology, FactTopologyError> {
-    if task.as_any().downcast_ref::<RunProgramTask>().is_some() {
+    if task.as_any().downcast_ref::<RunProgramTask>().is_some()
+        || task.as_any().downcast_ref::<Cairo1ProgramTask>().is_some()
+    {
         let output_runner_data = output_runner_data.ok_or(FactTopologyError::Internal(
             "Output runner data not set for program task"
                 .to_string()
@@ -491,10 +482,55 @@ mod tests {
         }
     }
 
+    #[rstest]
+    /// Composite outputs are replaced by the fact topologies of their plain subtasks, at any depth.
+    fn test_compute_fact_topologies_composite_output(fact_topologies: Vec<FactTopology>) {
+        let inner = CompositePackedOutput::new(
+            vec![],
+            vec![PackedOutput::Plain(vec![]), PackedOutput::Plain(vec![])],
+            vec![fact_topologies[1].clone(), fact_topologies[2].clone()],
+        );
+        let outer = CompositePackedOutput::new(
+            vec![],
+            vec![PackedOutput::Composite(inner), PackedOutput::Plain(vec![])],
+            vec![
+                FactTopology {
+                    tree_structure: vec![],
+                    page_sizes: vec![2],
+                },
+                fact_topologies[0].clone(),
+            ],
+        );
+        let packed_outputs = vec![PackedOutput::Plain(vec![]), PackedOutput::Composite(outer)];
+        let task_fact_topologies = vec![
+            fact_topologies[2].clone(),
+            FactTopology {
+                tree_structure: vec![],
+                page_sizes: vec![2],
+            },
+        ];
+
+        let plain_fact_topologies = compute_fact_topologies(&packed_outputs, &task_fact_topologies)
+            .expect("Failed to compute fact topologies");
+        assert_eq!(
+            plain_fact_topologies,
+            vec![
+                &fact_topologies[2],
+                &fact_topologies[1],
+                &fact_topologies[2],
+                &fact_topologies[0],
+            ]
+        );
+    }
+
     #[test]
-    /// Composite outputs are not supported (yet).
-    fn test_compute_fact_topologies_composite_output() {
-        let packed_outputs = vec![PackedOutput::Composite(CompositePackedOutput::default())];
+    /// A composite output must provide one fact topology per subtask.
+    fn test_compute_fact_topologies_composite_output_len_mismatch() {
+        let packed_outputs = vec![PackedOutput::Composite(CompositePackedOutput::new(
+            vec![],
+            vec![PackedOutput::Plain(vec![])],
+            vec![],
+        ))];
         let fact_topologies = vec![FactTopology {
             tree_structure: vec![],
             page_sizes: vec![],
@@ -502,7 +538,7 @@ mod tests {
         let result = compute_fact_topologies(&packed_outputs, &fact_topologies);
         assert!(matches!(
             result,
-            Err(FactTopologyError::CompositePackedOutputNotSupported(_))
+            Err(FactTopologyError::WrongNumberOfFactTopologies(1, 0))
         ));
     }
 
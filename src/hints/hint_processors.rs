@@ -5,9 +5,13 @@ use std::rc::Rc;
 use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::{
     BuiltinHintProcessor, HintFunc, HintProcessorData,
 };
-use cairo_vm::hint_processor::hint_processor_definition::{HintExtension, HintProcessorLogic};
+use cairo_vm::hint_processor::hint_processor_definition::{
+    HintExtension, HintProcessor, HintProcessorLogic, HintReference,
+};
+use cairo_vm::serde::deserialize_program::ApTracking;
 use cairo_vm::types::exec_scope::ExecutionScopes;
 use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
 use cairo_vm::vm::runners::cairo_runner::ResourceTracker;
 use cairo_vm::vm::vm_core::VirtualMachine;
 use cairo_vm::Felt252;
@@ -21,17 +25,27 @@ use crate::hints::bootloader_hints::{
     prepare_simple_bootloader_output_segment, restore_bootloader_output, save_output_pointer,
     save_packed_outputs, set_packed_output_to_subtasks,
 };
+use crate::hints::cairo1::Cairo1TaskHintProcessor;
+use crate::hints::cascading_hint_processor::{get_hint_code, CascadingHintProcessor};
 use crate::hints::codes::*;
 use crate::hints::execute_task_hints::{
     allocate_program_data_segment, append_fact_topologies, call_task, exit_scope_with_comments,
-    load_program_hint, validate_hash, write_return_builtins_hint,
+    get_task_program_hash, load_program_hint, validate_hash, write_return_builtins_hint,
+    CompiledHintsCache,
 };
+use crate::hints::fact_topologies::FactTopology;
 use crate::hints::inner_select_builtins::select_builtin;
+use crate::hints::pie_resources::{PieResourcesCheck, PieResourcesReport};
+use crate::hints::program_input_hints::{ProgramInputHintFunc, ProgramInputHints};
 use crate::hints::select_builtins::select_builtins_enter_scope;
 use crate::hints::simple_bootloader_hints::{
     divide_num_by_2, prepare_task_range_checks, set_ap_to_zero, set_ap_to_zero_or_one,
     set_current_task, set_tasks_variable,
 };
+use crate::hints::step_limits::{StepLimitExceeded, StepLimiter};
+use crate::hints::task_context::TaskContext;
+use crate::hints::types::{SimpleBootloaderInput, TaskHints, TaskSpec};
+use crate::hints::vars;
 
 /// A hint processor that can only execute the hints defined in this library.
 /// For large projects, you may want to compose a hint processor from multiple parts
@@ -41,14 +55,72 @@ use crate::hints::simple_bootloader_hints::{
 /// However, this processor is not sufficient to execute the bootloader. For this,
 /// use `BootloaderHintProcessor`.
 #[derive(Default)]
-pub struct MinimalBootloaderHintProcessor;
+pub struct MinimalBootloaderHintProcessor {
+    /// Number of VM steps executed so far. `None` until the VM reports a step through
+    /// `ResourceTracker::consume_step`, in which case task step counts are not checked.
+    n_steps: Option<usize>,
+    pie_resources_check: PieResourcesCheck,
+    pie_resources_reports: Vec<PieResourcesReport>,
+    step_limiter: StepLimiter,
+    /// The tasks being executed, from `SetCurrentTask` to `AppendFactTopologies`, the innermost
+    /// task last.
+    running_tasks: Vec<TaskContext>,
+    /// Hints of the task programs executed so far, compiled once per program.
+    compiled_hints_cache: CompiledHintsCache,
+    /// Fact topologies of the bootloader output, set once the bootloader computed them.
+    fact_topologies: Vec<FactTopology>,
+}
 
 impl MinimalBootloaderHintProcessor {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Sets how to react when the resources recorded in a Cairo PIE task do not match the run.
+    pub fn set_pie_resources_check(&mut self, pie_resources_check: PieResourcesCheck) {
+        self.pie_resources_check = pie_resources_check;
+    }
+
+    /// Resource consistency reports of the Cairo PIE tasks executed so far.
+    pub fn pie_resources_reports(&self) -> &[PieResourcesReport] {
+        &self.pie_resources_reports
+    }
+
+    /// Limits the number of VM steps of the whole run, `None` for no limit. The limits of the
+    /// tasks are set in their `TaskSpec`.
+    pub fn set_max_steps(&mut self, max_steps: Option<usize>) {
+        self.step_limiter.set_max_steps(max_steps);
+    }
+
+    /// The step limit that stopped the run, if any.
+    pub fn step_limit_exceeded(&self) -> Option<&StepLimitExceeded> {
+        self.step_limiter.exceeded()
+    }
+
+    /// The task of the simple bootloader input being executed. After a failed run, this is the
+    /// task that was executing when the run failed, `None` if it failed in the bootloader code.
+    pub fn current_task(&self) -> Option<&TaskContext> {
+        self.running_tasks.first()
+    }
+
+    /// Fact topologies of the plain outputs of the bootloader, one per task. Empty until the
+    /// bootloader computed them at the end of the run.
+    pub fn fact_topologies(&self) -> &[FactTopology] {
+        &self.fact_topologies
     }
 }
 
+/// Describes the task selected by `set_current_task`.
+fn get_current_task_context(exec_scopes: &ExecutionScopes) -> Result<TaskContext, HintError> {
+    let task_index: usize = exec_scopes.get(vars::TASK_ID)?;
+    let simple_bootloader_input: &SimpleBootloaderInput =
+        exec_scopes.get_ref(vars::SIMPLE_BOOTLOADER_INPUT)?;
+    Ok(TaskContext::new(
+        task_index,
+        &simple_bootloader_input.tasks[task_index],
+    ))
+}
+
 impl HintProcessorLogic for MinimalBootloaderHintProcessor {
     fn execute_hint_extensive(
         &mut self,
@@ -63,78 +135,125 @@ impl HintProcessorLogic for MinimalBootloaderHintProcessor {
 
         let ids_data = &hint_data.ids_data;
         let ap_tracking = &hint_data.ap_tracking;
-        let hint_extension = match hint_data.code.as_str() {
-            BOOTLOADER_RESTORE_BOOTLOADER_OUTPUT => restore_bootloader_output(vm, exec_scopes),
-            BOOTLOADER_PREPARE_SIMPLE_BOOTLOADER_INPUT => {
+        let Some(hint) = get_bootloader_hint(&hint_data.code) else {
+            return Err(HintError::UnknownHint(
+                hint_data.code.clone().into_boxed_str(),
+            ));
+        };
+
+        let hint_extension = match hint {
+            BootloaderHint::RestoreBootloaderOutput => restore_bootloader_output(vm, exec_scopes),
+            BootloaderHint::PrepareSimpleBootloaderInput => {
                 prepare_simple_bootloader_input(exec_scopes)
             }
-            BOOTLOADER_LOAD_BOOTLOADER_CONFIG => {
+            BootloaderHint::LoadBootloaderConfig => {
                 load_bootloader_config(vm, exec_scopes, ids_data, ap_tracking)
             }
-            BOOTLOADER_ENTER_PACKED_OUTPUT_SCOPE => {
+            BootloaderHint::EnterPackedOutputScope => {
                 enter_packed_output_scope(vm, exec_scopes, ids_data, ap_tracking)
             }
-            BOOTLOADER_SAVE_OUTPUT_POINTER => {
+            BootloaderHint::SaveOutputPointer => {
                 save_output_pointer(vm, exec_scopes, ids_data, ap_tracking)
             }
-            BOOTLOADER_SAVE_PACKED_OUTPUTS => save_packed_outputs(exec_scopes),
-            BOOTLOADER_GUESS_PRE_IMAGE_OF_SUBTASKS_OUTPUT_HASH => {
+            BootloaderHint::SavePackedOutputs => save_packed_outputs(exec_scopes),
+            BootloaderHint::GuessPreImageOfSubtasksOutputHash => {
                 guess_pre_image_of_subtasks_output_hash(vm, exec_scopes, ids_data, ap_tracking)
             }
-            BOOTLOADER_PREPARE_SIMPLE_BOOTLOADER_OUTPUT_SEGMENT => {
+            BootloaderHint::PrepareSimpleBootloaderOutputSegment => {
                 prepare_simple_bootloader_output_segment(vm, exec_scopes, ids_data, ap_tracking)
             }
-            BOOTLOADER_COMPUTE_FACT_TOPOLOGIES => {
-                compute_and_configure_fact_topologies(vm, exec_scopes)
+            BootloaderHint::ComputeFactTopologies => {
+                let hint_extension = compute_and_configure_fact_topologies(vm, exec_scopes)?;
+                self.fact_topologies = exec_scopes.get(vars::PLAIN_FACT_TOPOLOGIES)?;
+                Ok(hint_extension)
             }
-            BOOTLOADER_SET_PACKED_OUTPUT_TO_SUBTASKS => set_packed_output_to_subtasks(exec_scopes),
-            BOOTLOADER_IMPORT_PACKED_OUTPUT_SCHEMAS => import_packed_output_schemas(),
-            BOOTLOADER_IS_PLAIN_PACKED_OUTPUT => is_plain_packed_output(vm, exec_scopes),
-            BOOTLOADER_ASSERT_IS_COMPOSITE_PACKED_OUTPUT => {
+            BootloaderHint::SetPackedOutputToSubtasks => set_packed_output_to_subtasks(exec_scopes),
+            BootloaderHint::ImportPackedOutputSchemas => import_packed_output_schemas(),
+            BootloaderHint::IsPlainPackedOutput => is_plain_packed_output(vm, exec_scopes),
+            BootloaderHint::AssertIsCompositePackedOutput => {
                 assert_is_composite_packed_output(exec_scopes)
             }
-            SIMPLE_BOOTLOADER_PREPARE_TASK_RANGE_CHECKS => {
+            BootloaderHint::PrepareTaskRangeChecks => {
                 prepare_task_range_checks(vm, exec_scopes, ids_data, ap_tracking)
             }
-            SIMPLE_BOOTLOADER_SET_TASKS_VARIABLE => set_tasks_variable(exec_scopes),
-            SIMPLE_BOOTLOADER_DIVIDE_NUM_BY_2 => divide_num_by_2(vm, ids_data, ap_tracking),
-            SIMPLE_BOOTLOADER_SET_CURRENT_TASK => {
-                set_current_task(vm, exec_scopes, ids_data, ap_tracking)
+            BootloaderHint::SetTasksVariable => set_tasks_variable(exec_scopes),
+            BootloaderHint::DivideNumBy2 => divide_num_by_2(vm, ids_data, ap_tracking),
+            BootloaderHint::SetCurrentTask => {
+                let hint_extension = set_current_task(vm, exec_scopes, ids_data, ap_tracking)?;
+                self.running_tasks
+                    .push(get_current_task_context(exec_scopes)?);
+                Ok(hint_extension)
             }
-            SIMPLE_BOOTLOADER_ZERO_V0_13_0 => set_ap_to_zero(vm),
-            SIMPLE_BOOTLOADER_ZERO_V0_13_1 => {
+            BootloaderHint::SetApToZero => set_ap_to_zero(vm),
+            BootloaderHint::SetApToZeroOrOne => {
                 set_ap_to_zero_or_one(vm, exec_scopes, ids_data, ap_tracking)
             }
-            EXECUTE_TASK_ALLOCATE_PROGRAM_DATA_SEGMENT => {
+            BootloaderHint::AllocateProgramDataSegment => {
                 allocate_program_data_segment(vm, exec_scopes, ids_data, ap_tracking)
             }
-            EXECUTE_TASK_LOAD_PROGRAM => load_program_hint(vm, exec_scopes, ids_data, ap_tracking),
-            EXECUTE_TASK_VALIDATE_HASH_V0_13_0 => {
-                validate_hash(vm, exec_scopes, ids_data, ap_tracking)
-            }
-            EXECUTE_TASK_VALIDATE_HASH_V0_13_1 => {
-                validate_hash(vm, exec_scopes, ids_data, ap_tracking)
+            BootloaderHint::LoadProgram => {
+                load_program_hint(vm, exec_scopes, ids_data, ap_tracking)
             }
-            EXECUTE_TASK_ASSERT_PROGRAM_ADDRESS => {
+            BootloaderHint::ValidateHash => validate_hash(vm, exec_scopes, ids_data, ap_tracking),
+            BootloaderHint::AssertProgramAddress => {
                 assert_program_address(vm, exec_scopes, ids_data, ap_tracking)
             }
-            EXECUTE_TASK_CALL_TASK => call_task(self, vm, exec_scopes, ids_data, ap_tracking),
-            EXECUTE_TASK_WRITE_RETURN_BUILTINS => {
-                write_return_builtins_hint(vm, exec_scopes, ids_data, ap_tracking)
+            BootloaderHint::CallTask => {
+                let max_steps = exec_scopes.get_ref::<TaskSpec>(vars::TASK)?.max_steps();
+                // The bootloader asserted the program hash before calling the task, whether or not
+                // it also runs the hash validation hint.
+                let program_hash = get_task_program_hash(vm, ids_data, ap_tracking)?;
+                exec_scopes.insert_value(vars::PROGRAM_HASH, program_hash);
+                if let Some(task) = self.running_tasks.last_mut() {
+                    task.program_hash = Some(program_hash);
+                }
+                let task = self.running_tasks.last().cloned();
+                if let Some(n_steps) = self.n_steps {
+                    exec_scopes.insert_value(vars::TASK_START_STEP, n_steps);
+                }
+                // `call_task` compiles the task hints with this hint processor.
+                let mut compiled_hints_cache = std::mem::take(&mut self.compiled_hints_cache);
+                let result = call_task(
+                    self,
+                    &mut compiled_hints_cache,
+                    vm,
+                    exec_scopes,
+                    ids_data,
+                    ap_tracking,
+                );
+                self.compiled_hints_cache = compiled_hints_cache;
+                let hint_extension = result?;
+                self.step_limiter
+                    .enter_task(task, max_steps, self.n_steps.unwrap_or(0));
+                Ok(hint_extension)
             }
-            EXECUTE_TASK_APPEND_FACT_TOPOLOGIES => {
-                append_fact_topologies(vm, exec_scopes, ids_data, ap_tracking)
+            BootloaderHint::WriteReturnBuiltins => write_return_builtins_hint(
+                vm,
+                exec_scopes,
+                ids_data,
+                ap_tracking,
+                self.pie_resources_check,
+                &mut self.pie_resources_reports,
+            ),
+            BootloaderHint::AppendFactTopologies => {
+                let hint_extension =
+                    append_fact_topologies(vm, exec_scopes, ids_data, ap_tracking)?;
+                // The last hint of the task: the ones before it still fail on behalf of the task.
+                self.running_tasks.pop();
+                Ok(hint_extension)
             }
-            EXECUTE_TASK_EXIT_SCOPE => exit_scope_with_comments(exec_scopes),
-            SELECT_BUILTINS_ENTER_SCOPE => {
-                select_builtins_enter_scope(vm, exec_scopes, ids_data, ap_tracking)
+            BootloaderHint::ExitTaskScope => {
+                let hint_extension = exit_scope_with_comments(exec_scopes)?;
+                if let Some(n_steps) = self.n_steps {
+                    exec_scopes.insert_value(vars::TASK_END_STEP, n_steps);
+                }
+                self.step_limiter.exit_task();
+                Ok(hint_extension)
             }
-            INNER_SELECT_BUILTINS_SELECT_BUILTIN => {
-                select_builtin(vm, exec_scopes, ids_data, ap_tracking)
+            BootloaderHint::SelectBuiltinsEnterScope => {
+                select_builtins_enter_scope(vm, exec_scopes, ids_data, ap_tracking)
             }
-            unknown_hint_code => Err(HintError::UnknownHint(
-                unknown_hint_code.to_string().into_boxed_str(),
-            )),
+            BootloaderHint::SelectBuiltin => select_builtin(vm, exec_scopes, ids_data, ap_tracking),
         };
 
         hint_extension
@@ -155,17 +274,113 @@ impl HintProcessorLogic for MinimalBootloaderHintProcessor {
     }
 }
 
-impl ResourceTracker for MinimalBootloaderHintProcessor {}
+impl ResourceTracker for MinimalBootloaderHintProcessor {
+    fn consumed(&self) -> bool {
+        self.step_limiter.exceeded().is_some()
+    }
+
+    fn consume_step(&mut self) {
+        let n_steps = self.n_steps.get_or_insert(0);
+        *n_steps += 1;
+        self.step_limiter.check(*n_steps);
+    }
+}
 
 /// A hint processor for use cases where we only care about the bootloader hints.
 ///
-/// When executing a hint, this hint processor will first check the hints defined in this library,
-/// then the ones defined in Cairo VM.
+/// This hint processor is a `CascadingHintProcessor` checking the hints defined in this library,
+/// then the hints of the task being executed, the ones defined in Cairo VM and finally the hints
+/// reading the input of program tasks.
 pub struct BootloaderHintProcessor {
     bootloader_hint_processor: MinimalBootloaderHintProcessor,
+    running_tasks: RunningTasks,
     builtin_hint_processor: BuiltinHintProcessor,
+    program_input_hints: ProgramInputHints,
+}
+
+/// The tasks being executed, the innermost task last. Every called task pushes an entry, even
+/// without hints, so that leaving its scope pops the right one.
+///
+/// As a hint processor, executes the hints of the innermost task.
+#[derive(Default)]
+struct RunningTasks(Vec<RunningTask>);
+
+/// Hints of a task being executed.
+#[derive(Default)]
+struct RunningTask {
+    hints: TaskHints,
+    /// Set for Cairo 1 tasks, whose hints are all Cairo 1 hints.
+    cairo1_hint_processor: Option<Cairo1TaskHintProcessor>,
 }
 
+impl RunningTask {
+    /// Reads the task about to be called, before `call_task` enters its scope.
+    fn from_current_task(exec_scopes: &ExecutionScopes) -> Result<Self, HintError> {
+        let task: &TaskSpec = exec_scopes.get_ref(vars::TASK)?;
+        Ok(match task {
+            TaskSpec::RunProgram(task) => Self {
+                hints: task.hints.clone(),
+                ..Default::default()
+            },
+            TaskSpec::RunCairo1Program(task) => Self {
+                cairo1_hint_processor: Some(Cairo1TaskHintProcessor::new(task)),
+                ..Default::default()
+            },
+            _ => Self::default(),
+        })
+    }
+}
+
+impl HintProcessorLogic for RunningTasks {
+    fn execute_hint(
+        &mut self,
+        _vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        hint_data: &Box<dyn Any>,
+        _constants: &HashMap<String, Felt>,
+    ) -> Result<(), HintError> {
+        // This method will never be called, but must be defined for `HintProcessorLogic`.
+
+        let hint_code = get_hint_code(&**hint_data).ok_or(HintError::WrongHintData)?;
+        Err(HintError::UnknownHint(hint_code.into()))
+    }
+
+    fn execute_hint_extensive(
+        &mut self,
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data: &Box<dyn Any>,
+        constants: &HashMap<String, Felt>,
+    ) -> Result<HintExtension, HintError> {
+        let hint_processor_data = hint_data
+            .downcast_ref::<HintProcessorData>()
+            .ok_or(HintError::WrongHintData)?;
+        let hint_code = hint_processor_data.code.as_str();
+        let Some(running_task) = self.0.last_mut() else {
+            return Err(HintError::UnknownHint(hint_code.into()));
+        };
+
+        if let Some(cairo1_hint_processor) = &mut running_task.cairo1_hint_processor {
+            return cairo1_hint_processor.execute_hint(vm, exec_scopes, hint_code, constants);
+        }
+
+        let hint_func = running_task
+            .hints
+            .get(hint_code)
+            .ok_or_else(|| HintError::UnknownHint(hint_code.into()))?;
+        (hint_func.0)(
+            vm,
+            exec_scopes,
+            &hint_processor_data.ids_data,
+            &hint_processor_data.ap_tracking,
+            constants,
+        )?;
+        Ok(HashMap::new())
+    }
+}
+
+impl ResourceTracker for RunningTasks {}
+
 impl Default for BootloaderHintProcessor {
     fn default() -> Self {
         Self::new()
@@ -175,8 +390,10 @@ impl Default for BootloaderHintProcessor {
 impl BootloaderHintProcessor {
     pub fn new() -> Self {
         Self {
-            bootloader_hint_processor: MinimalBootloaderHintProcessor {},
+            bootloader_hint_processor: MinimalBootloaderHintProcessor::new(),
+            running_tasks: RunningTasks::default(),
             builtin_hint_processor: BuiltinHintProcessor::new_empty(),
+            program_input_hints: ProgramInputHints::default(),
         }
     }
 
@@ -185,6 +402,65 @@ impl BootloaderHintProcessor {
             .extra_hints
             .insert(hint_code, hint_func);
     }
+
+    /// Adds a hint reading the input of the program task being executed. Hints assigning
+    /// `program_input` values to `ids` variables (ex: `ids.x = program_input['x']`) are already
+    /// implemented.
+    pub fn add_program_input_hint(
+        &mut self,
+        hint_code: String,
+        hint_func: Rc<ProgramInputHintFunc>,
+    ) {
+        self.program_input_hints.insert(hint_code, hint_func);
+    }
+
+    /// Whether `hint_code` was added with `add_hint` or reads the program input.
+    pub(crate) fn implements_extra_hint(&self, hint_code: &str) -> bool {
+        self.builtin_hint_processor
+            .extra_hints
+            .contains_key(hint_code)
+            || self.program_input_hints.implements(hint_code)
+    }
+
+    /// Sets how to react when the resources recorded in a Cairo PIE task do not match the run.
+    pub fn set_pie_resources_check(&mut self, pie_resources_check: PieResourcesCheck) {
+        self.bootloader_hint_processor
+            .set_pie_resources_check(pie_resources_check);
+    }
+
+    /// Resource consistency reports of the Cairo PIE tasks executed so far.
+    pub fn pie_resources_reports(&self) -> &[PieResourcesReport] {
+        self.bootloader_hint_processor.pie_resources_reports()
+    }
+
+    /// Limits the number of VM steps of the whole run, `None` for no limit. The limits of the
+    /// tasks are set in their `TaskSpec`.
+    pub fn set_max_steps(&mut self, max_steps: Option<usize>) {
+        self.bootloader_hint_processor.set_max_steps(max_steps);
+    }
+
+    /// The step limit that stopped the run, if any.
+    pub fn step_limit_exceeded(&self) -> Option<&StepLimitExceeded> {
+        self.bootloader_hint_processor.step_limit_exceeded()
+    }
+
+    /// The task of the simple bootloader input being executed. After a failed run, this is the
+    /// task that was executing when the run failed, `None` if it failed in the bootloader code.
+    pub fn current_task(&self) -> Option<&TaskContext> {
+        self.bootloader_hint_processor.current_task()
+    }
+
+    /// Fact topologies of the plain outputs of the bootloader, one per task. Empty until the
+    /// bootloader computed them at the end of the run.
+    pub fn fact_topologies(&self) -> &[FactTopology] {
+        self.bootloader_hint_processor.fact_topologies()
+    }
+
+    /// Number of task programs whose compiled hints are cached.
+    #[cfg(test)]
+    pub(crate) fn n_cached_task_programs(&self) -> usize {
+        self.bootloader_hint_processor.compiled_hints_cache.len()
+    }
 }
 
 impl HintProcessorLogic for BootloaderHintProcessor {
@@ -197,9 +473,30 @@ impl HintProcessorLogic for BootloaderHintProcessor {
     ) -> Result<(), HintError> {
         // This method will never be called, but must be defined for `HintProcessorLogic`.
 
-        let hint_data = hint_data.downcast_ref::<HintProcessorData>().unwrap();
-        let hint_code = &hint_data.code;
-        Err(HintError::UnknownHint(hint_code.clone().into_boxed_str()))
+        let hint_code = get_hint_code(&**hint_data).ok_or(HintError::WrongHintData)?;
+        Err(HintError::UnknownHint(hint_code.into()))
+    }
+
+    fn compile_hint(
+        &self,
+        hint_code: &str,
+        ap_tracking_data: &ApTracking,
+        reference_ids: &HashMap<String, usize>,
+        references: &[HintReference],
+    ) -> Result<Box<dyn Any>, VirtualMachineError> {
+        let layers: [&dyn HintProcessor; 4] = [
+            &self.bootloader_hint_processor,
+            &self.running_tasks,
+            &self.builtin_hint_processor,
+            &self.program_input_hints,
+        ];
+        CascadingHintProcessor::compile_in_layers(
+            layers,
+            hint_code,
+            ap_tracking_data,
+            reference_ids,
+            references,
+        )
     }
 
     fn execute_hint_extensive(
@@ -209,23 +506,38 @@ impl HintProcessorLogic for BootloaderHintProcessor {
         hint_data: &Box<dyn Any>,
         constants: &HashMap<String, Felt>,
     ) -> Result<HintExtension, HintError> {
-        // Cascade through the internal hint processors until we find the hint implementation.
+        let hint_code = get_hint_code(&**hint_data).ok_or(HintError::WrongHintData)?;
+        let bootloader_hint = get_bootloader_hint(hint_code);
 
-        match self.bootloader_hint_processor.execute_hint_extensive(
-            vm,
-            exec_scopes,
-            hint_data,
-            constants,
-        ) {
-            Err(HintError::UnknownHint(_)) => {}
-            result => {
-                return result;
+        let called_task = match bootloader_hint {
+            Some(BootloaderHint::CallTask) => Some(RunningTask::from_current_task(exec_scopes)?),
+            _ => None,
+        };
+
+        let mut hint_processor = CascadingHintProcessor::new();
+        hint_processor.add_processor(&mut self.bootloader_hint_processor);
+        hint_processor.add_processor(&mut self.running_tasks);
+        hint_processor.add_processor(&mut self.builtin_hint_processor);
+        hint_processor.add_processor(&mut self.program_input_hints);
+        let result = hint_processor.execute_hint_extensive(vm, exec_scopes, hint_data, constants);
+
+        if result.is_ok() {
+            if let Some(running_task) = called_task {
+                self.running_tasks.0.push(running_task);
+            } else if bootloader_hint == Some(BootloaderHint::ExitTaskScope) {
+                self.running_tasks.0.pop();
             }
         }
-
-        self.builtin_hint_processor
-            .execute_hint_extensive(vm, exec_scopes, hint_data, constants)
+        result
     }
 }
 
-impl ResourceTracker for BootloaderHintProcessor {}
+impl ResourceTracker for BootloaderHintProcessor {
+    fn consumed(&self) -> bool {
+        self.bootloader_hint_processor.consumed()
+    }
+
+    fn consume_step(&mut self) {
+        self.bootloader_hint_processor.consume_step();
+    }
+}
@@ -0,0 +1,255 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use cairo_vm::hint_processor::hint_processor_definition::{
+    HintExtension, HintProcessor, HintProcessorLogic, HintReference,
+};
+use cairo_vm::serde::deserialize_program::ApTracking;
+use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+use cairo_vm::vm::runners::cairo_runner::{ResourceTracker, RunResources};
+use cairo_vm::vm::vm_core::VirtualMachine;
+use cairo_vm::Felt252;
+
+use crate::hints::cascading_hint_processor::get_hint_code;
+use crate::hints::codes::{get_bootloader_hint, BootloaderHint};
+use crate::hints::vars;
+
+/// Hints that are not bootloader hints are shown by the start of their code.
+const HINT_CODE_PREVIEW_LENGTH: usize = 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HintStats {
+    pub calls: usize,
+    /// Wall time spent in the hint implementations, failed calls included.
+    pub total_time: Duration,
+}
+
+/// Statistics of one hint, executed under one task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HintProfileEntry {
+    /// Index of the (top-level) task being executed, `None` for the hints of the bootloader.
+    pub task_index: Option<usize>,
+    /// Name of the bootloader hint, or the start of the code of other hints.
+    pub hint: String,
+    pub stats: HintStats,
+}
+
+/// Hints executed during a run, the most expensive first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HintProfile {
+    pub entries: Vec<HintProfileEntry>,
+}
+
+impl HintProfile {
+    pub fn total(&self) -> HintStats {
+        self.entries
+            .iter()
+            .fold(HintStats::default(), |total, entry| HintStats {
+                calls: total.calls + entry.stats.calls,
+                total_time: total.total_time + entry.stats.total_time,
+            })
+    }
+}
+
+impl fmt::Display for HintProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total();
+        write!(
+            f,
+            "Hint profile: {} calls, {:.3?} in total",
+            total.calls, total.total_time
+        )?;
+        write!(f, "\n  {:>6} {:>10} {:>12}  hint", "task", "calls", "time")?;
+        for entry in &self.entries {
+            let task = entry
+                .task_index
+                .map(|task_index| task_index.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            write!(
+                f,
+                "\n  {:>6} {:>10} {:>12}  {}",
+                task,
+                entry.stats.calls,
+                format!("{:.3?}", entry.stats.total_time),
+                entry.hint
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps a hint processor to record the hints it executes: calls and wall time per hint and
+/// task.
+///
+/// Profiling adds a map lookup and two clock reads per hint, only use it to investigate runs.
+pub struct HintProfiler<P: HintProcessor> {
+    hint_processor: P,
+    stats: HashMap<(Option<usize>, String), HintStats>,
+    /// Indices of the tasks being executed, the innermost task last. Every called task pushes an
+    /// entry, so that leaving its scope pops the right one.
+    running_tasks: Vec<Option<usize>>,
+}
+
+impl<P: HintProcessor> HintProfiler<P> {
+    pub fn new(hint_processor: P) -> Self {
+        Self {
+            hint_processor,
+            stats: HashMap::new(),
+            running_tasks: vec![],
+        }
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.hint_processor
+    }
+
+    pub fn into_inner(self) -> P {
+        self.hint_processor
+    }
+
+    /// Summary of the hints executed so far.
+    pub fn profile(&self) -> HintProfile {
+        let mut entries: Vec<HintProfileEntry> = self
+            .stats
+            .iter()
+            .map(|((task_index, hint_code), stats)| HintProfileEntry {
+                task_index: *task_index,
+                hint: get_bootloader_hint(hint_code)
+                    .map(|hint| format!("{hint:?}"))
+                    .unwrap_or_else(|| preview_hint_code(hint_code)),
+                stats: *stats,
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            b.stats
+                .total_time
+                .cmp(&a.stats.total_time)
+                .then_with(|| a.task_index.cmp(&b.task_index))
+                .then_with(|| a.hint.cmp(&b.hint))
+        });
+        HintProfile { entries }
+    }
+}
+
+fn preview_hint_code(hint_code: &str) -> String {
+    let first_line = hint_code.lines().next().unwrap_or_default();
+    let mut preview: String = first_line.chars().take(HINT_CODE_PREVIEW_LENGTH).collect();
+    if preview.len() < hint_code.len() {
+        preview.push_str("...");
+    }
+    preview
+}
+
+impl<P: HintProcessor> HintProcessorLogic for HintProfiler<P> {
+    fn execute_hint(
+        &mut self,
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data: &Box<dyn Any>,
+        constants: &HashMap<String, Felt252>,
+    ) -> Result<(), HintError> {
+        self.hint_processor
+            .execute_hint(vm, exec_scopes, hint_data, constants)
+    }
+
+    fn compile_hint(
+        &self,
+        hint_code: &str,
+        ap_tracking_data: &ApTracking,
+        reference_ids: &HashMap<String, usize>,
+        references: &[HintReference],
+    ) -> Result<Box<dyn Any>, VirtualMachineError> {
+        self.hint_processor
+            .compile_hint(hint_code, ap_tracking_data, reference_ids, references)
+    }
+
+    fn execute_hint_extensive(
+        &mut self,
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data: &Box<dyn Any>,
+        constants: &HashMap<String, Felt252>,
+    ) -> Result<HintExtension, HintError> {
+        let hint_code = get_hint_code(&**hint_data).unwrap_or("<unknown hint data>");
+        let bootloader_hint = get_bootloader_hint(hint_code);
+        // The task index is only visible in the scope of the bootloader, before the task scope.
+        let called_task = match bootloader_hint {
+            Some(BootloaderHint::CallTask) => exec_scopes.get::<usize>(vars::TASK_ID).ok(),
+            _ => None,
+        };
+        let task_index = self.running_tasks.first().copied().flatten();
+
+        let start = Instant::now();
+        let result =
+            self.hint_processor
+                .execute_hint_extensive(vm, exec_scopes, hint_data, constants);
+        let elapsed = start.elapsed();
+
+        let stats = self
+            .stats
+            .entry((task_index, hint_code.to_string()))
+            .or_default();
+        stats.calls += 1;
+        stats.total_time += elapsed;
+
+        if result.is_ok() {
+            match bootloader_hint {
+                Some(BootloaderHint::CallTask) => self.running_tasks.push(called_task),
+                Some(BootloaderHint::ExitTaskScope) => {
+                    self.running_tasks.pop();
+                }
+                _ => {}
+            }
+        }
+        result
+    }
+}
+
+impl<P: HintProcessor> ResourceTracker for HintProfiler<P> {
+    fn consumed(&self) -> bool {
+        self.hint_processor.consumed()
+    }
+
+    fn consume_step(&mut self) {
+        self.hint_processor.consume_step()
+    }
+
+    fn get_n_steps(&self) -> Option<usize> {
+        self.hint_processor.get_n_steps()
+    }
+
+    fn run_resources(&self) -> &RunResources {
+        self.hint_processor.run_resources()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::bootloader_hint(crate::hints::codes::EXECUTE_TASK_CALL_TASK, "CallTask")]
+    #[case::short("memory[ap] = 1", "memory[ap] = 1")]
+    #[case::multiline("a = 1\nb = 2", "a = 1...")]
+    fn test_profile_hint_names(#[case] hint_code: &str, #[case] expected: &str) {
+        let mut profiler = HintProfiler::new(crate::BootloaderHintProcessor::new());
+        profiler.stats.insert(
+            (Some(0), hint_code.to_string()),
+            HintStats {
+                calls: 2,
+                total_time: Duration::from_millis(3),
+            },
+        );
+
+        let profile = profiler.profile();
+        assert_eq!(profile.entries.len(), 1);
+        assert_eq!(profile.entries[0].hint, expected);
+        assert_eq!(profile.total().calls, 2);
+    }
+}
@@ -61,7 +61,7 @@ pub enum CairoPieLoaderError {
 
 impl From<CairoPieLoaderError> for HintError {
     fn from(value: CairoPieLoaderError) -> Self {
-        HintError::CustomHint(value.to_string().into_boxed_str())
+        crate::Error::from(value).into()
     }
 }
 
@@ -1,21 +1,50 @@
 mod bootloader_hints;
+mod cairo1;
+mod cairo_vm_hints;
+mod cascading_hint_processor;
 mod codes;
 mod execute_task_hints;
 mod fact_topologies;
 mod hint_processors;
+mod hint_profiler;
 mod inner_select_builtins;
 mod load_cairo_pie;
+mod pie_resources;
+mod preflight;
 mod program_hash;
+mod program_input_hints;
 mod program_loader;
 mod select_builtins;
 mod simple_bootloader_hints;
+mod step_limits;
+mod task_context;
 mod types;
 mod vars;
 
+pub use cairo1::Cairo1TaskError;
+pub use cascading_hint_processor::CascadingHintProcessor;
+pub use fact_topologies::{
+    get_task_fact_topology, FactTopology, FactTopologyError, PageError, TreeStructureError,
+    WriteFactTopologiesError,
+};
 pub use hint_processors::{BootloaderHintProcessor, MinimalBootloaderHintProcessor};
+pub use hint_profiler::{HintProfile, HintProfileEntry, HintProfiler, HintStats};
+pub use load_cairo_pie::{
+    CairoPieLoaderError, MemoryRelocationError, RelocationTableError, SignatureRelocationError,
+};
+pub use pie_resources::{PieResourcesCheck, PieResourcesMismatch, PieResourcesReport};
+pub use preflight::{
+    check_task_hints, TaskUnsupportedHints, UnsupportedHint, UnsupportedHintsError,
+};
+pub use program_hash::{compute_program_hash_chain, HashChainError, ProgramHashError};
+pub use program_input_hints::{parse_program_input, ProgramInput, ProgramInputHintFunc};
+pub use program_loader::ProgramLoaderError;
+pub use step_limits::StepLimitExceeded;
+pub use task_context::{TaskContext, TaskExecutionError, TaskKind};
 pub use types::{
-    BootloaderConfig, BootloaderInput, CairoPiePath, CairoPieTask, PackedOutput, RunProgramTask,
-    SimpleBootloaderInput, Task, TaskSpec,
+    BootloaderConfig, BootloaderInput, Cairo1ProgramTask, CairoPieBytes, CairoPiePath,
+    CairoPieTask, CompositePackedOutput, PackedOutput, RunProgramTask, SimpleBootloaderInput, Task,
+    TaskHints, TaskSpec,
 };
 
 pub use vars::BOOTLOADER_INPUT;
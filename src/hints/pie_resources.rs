@@ -0,0 +1,277 @@
+use std::fmt;
+
+use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::types::relocatable::Relocatable;
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::runners::cairo_pie::CairoPie;
+use cairo_vm::vm::vm_core::VirtualMachine;
+
+/// Controls what happens when the execution resources recorded in a Cairo PIE do not match
+/// what the bootloader observed while executing it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PieResourcesCheck {
+    /// Fail the run on the first inconsistent PIE task.
+    #[default]
+    Strict,
+    /// Only record the inconsistencies, useful to debug third-party PIEs.
+    WarnOnly,
+}
+
+/// A single difference between the resources recorded in a Cairo PIE and the bootloader run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PieResourcesMismatch {
+    Steps {
+        recorded: usize,
+        observed: usize,
+    },
+    /// The bootloader can only observe a lower bound of the memory holes of a task: the cells
+    /// of its execution segment that were never written.
+    MemoryHoles {
+        recorded: usize,
+        observed_at_least: usize,
+    },
+    BuiltinSegmentSize {
+        builtin: BuiltinName,
+        recorded: usize,
+        observed: usize,
+    },
+    BuiltinInstances {
+        builtin: BuiltinName,
+        recorded: usize,
+        observed: usize,
+    },
+}
+
+impl fmt::Display for PieResourcesMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PieResourcesMismatch::Steps { recorded, observed } => {
+                write!(f, "n_steps: PIE records {recorded}, observed {observed}")
+            }
+            PieResourcesMismatch::MemoryHoles {
+                recorded,
+                observed_at_least,
+            } => write!(
+                f,
+                "n_memory_holes: PIE records {recorded}, observed at least {observed_at_least}"
+            ),
+            PieResourcesMismatch::BuiltinSegmentSize {
+                builtin,
+                recorded,
+                observed,
+            } => write!(
+                f,
+                "{} segment size: PIE records {recorded}, observed {observed}",
+                builtin.to_str()
+            ),
+            PieResourcesMismatch::BuiltinInstances {
+                builtin,
+                recorded,
+                observed,
+            } => write!(
+                f,
+                "{} instances: PIE records {recorded}, observed {observed}",
+                builtin.to_str()
+            ),
+        }
+    }
+}
+
+/// Consistency report of the execution resources of a single Cairo PIE task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PieResourcesReport {
+    /// Index of the task in the simple bootloader input.
+    pub task_index: usize,
+    pub mismatches: Vec<PieResourcesMismatch>,
+}
+
+impl PieResourcesReport {
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl fmt::Display for PieResourcesReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_consistent() {
+            return write!(
+                f,
+                "Task {}: resources are consistent with the CairoPie.",
+                self.task_index
+            );
+        }
+
+        write!(
+            f,
+            "Task {}: execution resources are inconsistent with the CairoPie:",
+            self.task_index
+        )?;
+        for mismatch in &self.mismatches {
+            write!(f, "\n  {mismatch}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What the bootloader observed while executing a Cairo PIE task.
+pub(crate) struct ObservedPieResources {
+    /// Steps executed between the call to the task and its return, if step tracking is enabled.
+    pub n_steps: Option<usize>,
+    /// Base of the PIE execution segment in the bootloader memory.
+    pub execution_segment_address: Relocatable,
+    /// Builtin segment sizes, computed from the builtin pointers before and after the task.
+    pub builtin_sizes: Vec<(BuiltinName, usize)>,
+}
+
+fn count_unwritten_cells(vm: &VirtualMachine, base: Relocatable, size: usize) -> usize {
+    (0..size)
+        .filter(|offset| {
+            (base + *offset)
+                .map(|address| vm.get_maybe(&address).is_none())
+                .unwrap_or(false)
+        })
+        .count()
+}
+
+fn get_cells_per_instance(vm: &VirtualMachine, builtin: &BuiltinName) -> usize {
+    vm.builtin_runners
+        .iter()
+        .find(|runner| runner.name() == *builtin)
+        .map(|runner| runner.cells_per_instance() as usize)
+        .unwrap_or(1)
+}
+
+/// Compares the execution resources recorded in `cairo_pie` with the ones observed by the
+/// bootloader.
+pub(crate) fn check_cairo_pie_resources(
+    vm: &VirtualMachine,
+    task_index: usize,
+    cairo_pie: &CairoPie,
+    observed: &ObservedPieResources,
+) -> PieResourcesReport {
+    let recorded = &cairo_pie.execution_resources;
+    let mut mismatches = vec![];
+
+    if let Some(n_steps) = observed.n_steps {
+        if n_steps != recorded.n_steps {
+            mismatches.push(PieResourcesMismatch::Steps {
+                recorded: recorded.n_steps,
+                observed: n_steps,
+            });
+        }
+    }
+
+    let unwritten_cells = count_unwritten_cells(
+        vm,
+        observed.execution_segment_address,
+        cairo_pie.metadata.execution_segment.size,
+    );
+    if unwritten_cells > recorded.n_memory_holes {
+        mismatches.push(PieResourcesMismatch::MemoryHoles {
+            recorded: recorded.n_memory_holes,
+            observed_at_least: unwritten_cells,
+        });
+    }
+
+    for (builtin, observed_size) in &observed.builtin_sizes {
+        let recorded_size = cairo_pie
+            .metadata
+            .builtin_segments
+            .get(builtin)
+            .map(|segment| segment.size)
+            .unwrap_or(0);
+        if recorded_size != *observed_size {
+            mismatches.push(PieResourcesMismatch::BuiltinSegmentSize {
+                builtin: *builtin,
+                recorded: recorded_size,
+                observed: *observed_size,
+            });
+        }
+
+        let recorded_instances = recorded
+            .builtin_instance_counter
+            .get(builtin)
+            .copied()
+            .unwrap_or(0);
+        let observed_instances = observed_size.div_ceil(get_cells_per_instance(vm, builtin));
+        if recorded_instances != observed_instances {
+            mismatches.push(PieResourcesMismatch::BuiltinInstances {
+                builtin: *builtin,
+                recorded: recorded_instances,
+                observed: observed_instances,
+            });
+        }
+    }
+
+    PieResourcesReport {
+        task_index,
+        mismatches,
+    }
+}
+
+/// Handles a report according to `check`: fails on inconsistencies in strict mode.
+pub(crate) fn enforce_pie_resources_check(
+    report: &PieResourcesReport,
+    check: PieResourcesCheck,
+) -> Result<(), HintError> {
+    match check {
+        PieResourcesCheck::Strict if !report.is_consistent() => {
+            Err(crate::Error::PieResources(report.clone()).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_display_names_builtin_and_sizes() {
+        let report = PieResourcesReport {
+            task_index: 3,
+            mismatches: vec![
+                PieResourcesMismatch::Steps {
+                    recorded: 10,
+                    observed: 12,
+                },
+                PieResourcesMismatch::BuiltinSegmentSize {
+                    builtin: BuiltinName::pedersen,
+                    recorded: 6,
+                    observed: 9,
+                },
+            ],
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "Task 3: execution resources are inconsistent with the CairoPie:\n  \
+             n_steps: PIE records 10, observed 12\n  \
+             pedersen segment size: PIE records 6, observed 9"
+        );
+    }
+
+    #[test]
+    fn test_enforce_pie_resources_check() {
+        let report = PieResourcesReport {
+            task_index: 0,
+            mismatches: vec![PieResourcesMismatch::BuiltinInstances {
+                builtin: BuiltinName::bitwise,
+                recorded: 1,
+                observed: 2,
+            }],
+        };
+
+        assert!(matches!(
+            enforce_pie_resources_check(&report, PieResourcesCheck::Strict),
+            Err(HintError::Internal(_))
+        ));
+        assert!(enforce_pie_resources_check(&report, PieResourcesCheck::WarnOnly).is_ok());
+
+        let consistent_report = PieResourcesReport {
+            task_index: 0,
+            mismatches: vec![],
+        };
+        assert!(enforce_pie_resources_check(&consistent_report, PieResourcesCheck::Strict).is_ok());
+    }
+}
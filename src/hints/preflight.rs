@@ -0,0 +1,175 @@
+use std::fmt;
+
+use cairo_vm::hint_processor::hint_processor_definition::HintProcessorLogic;
+
+use crate::hints::cairo_vm_hints::is_cairo_vm_hint;
+use crate::hints::codes::get_bootloader_hint;
+use crate::hints::hint_processors::BootloaderHintProcessor;
+use crate::hints::types::{RunProgramTask, TaskSpec};
+
+/// A hint of a task program that the hint processor cannot execute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedHint {
+    /// Offset of the hint in the task program.
+    pub pc: usize,
+    pub code: String,
+    /// Compilation error, `None` if the hint compiled but is not implemented.
+    pub compile_error: Option<String>,
+}
+
+/// The unsupported hints of one task, in PC order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskUnsupportedHints {
+    /// Index of the task in the task list.
+    pub task_index: usize,
+    pub hints: Vec<UnsupportedHint>,
+}
+
+/// The unsupported hints of all the tasks that have some.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedHintsError(pub Vec<TaskUnsupportedHints>);
+
+impl fmt::Display for UnsupportedHintsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The hint processor does not support some task hints:")?;
+        for task in &self.0 {
+            write!(f, "\n  Task {}:", task.task_index)?;
+            for hint in &task.hints {
+                write!(f, "\n    pc {}: {}", hint.pc, hint.code)?;
+                if let Some(compile_error) = &hint.compile_error {
+                    write!(f, " ({compile_error})")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnsupportedHintsError {}
+
+/// Checks, before running the bootloader, that `hint_processor` can execute every hint of the
+/// `RunProgramTask`s in `tasks`. Other tasks are not checked.
+///
+/// Hints are recognized by their code, without executing them: a hint is supported if it is a
+/// bootloader hint, a hint of the task itself (`RunProgramTask::hints`), a hint added to
+/// `hint_processor`, a hint reading the program input or a Cairo VM hint.
+pub fn check_task_hints(
+    tasks: &[TaskSpec],
+    hint_processor: &BootloaderHintProcessor,
+) -> Result<(), UnsupportedHintsError> {
+    let unsupported_hints: Vec<TaskUnsupportedHints> = tasks
+        .iter()
+        .enumerate()
+        .filter_map(|(task_index, task)| match task {
+            TaskSpec::RunProgram(task) => Some(TaskUnsupportedHints {
+                task_index,
+                hints: find_unsupported_hints(task, hint_processor),
+            }),
+            _ => None,
+        })
+        .filter(|task| !task.hints.is_empty())
+        .collect();
+
+    if unsupported_hints.is_empty() {
+        Ok(())
+    } else {
+        Err(UnsupportedHintsError(unsupported_hints))
+    }
+}
+
+fn find_unsupported_hints(
+    task: &RunProgramTask,
+    hint_processor: &BootloaderHintProcessor,
+) -> Vec<UnsupportedHint> {
+    let program = &task.program;
+    let hints = program.get_hints();
+    let mut hint_ranges: Vec<_> = program.get_hints_ranges().iter().collect();
+    hint_ranges.sort_by_key(|(pc, _)| pc.offset);
+
+    let mut unsupported_hints = vec![];
+    for (pc, (start, length)) in hint_ranges {
+        for hint in &hints[*start..*start + length.get()] {
+            if let Err(e) = hint_processor.compile_hint(
+                &hint.code,
+                &hint.flow_tracking_data.ap_tracking,
+                &hint.flow_tracking_data.reference_ids,
+                program.get_references(),
+            ) {
+                unsupported_hints.push(UnsupportedHint {
+                    pc: pc.offset,
+                    code: hint.code.clone(),
+                    compile_error: Some(e.to_string()),
+                });
+                continue;
+            }
+
+            let is_supported = task.hints.get(&hint.code).is_some()
+                || get_bootloader_hint(&hint.code).is_some()
+                || hint_processor.implements_extra_hint(&hint.code)
+                || is_cairo_vm_hint(&hint.code);
+            if !is_supported {
+                unsupported_hints.push(UnsupportedHint {
+                    pc: pc.offset,
+                    code: hint.code.clone(),
+                    compile_error: None,
+                });
+            }
+        }
+    }
+    unsupported_hints
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use assert_matches::assert_matches;
+    use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintFunc;
+    use cairo_vm::types::program::Program;
+
+    use super::*;
+
+    const FIBONACCI_WITH_HINT: &[u8] = include_bytes!("../../examples/fibonacci_with_hint.json");
+    const FIBONACCI_CLAIM_INDEX_HINT: &str =
+        "ids.fibonacci_claim_index = program_input['fibonacci_claim_index']";
+    const CUSTOM_CLAIM_INDEX_HINT: &str = "ids.fibonacci_claim_index = custom_claim_index()";
+
+    fn fibonacci_with_hint_task(hint_code: &str) -> RunProgramTask {
+        let program_json = std::str::from_utf8(FIBONACCI_WITH_HINT)
+            .unwrap()
+            .replace(FIBONACCI_CLAIM_INDEX_HINT, hint_code);
+        RunProgramTask::new(
+            Program::from_bytes(program_json.as_bytes(), Some("main")).unwrap(),
+            HashMap::new(),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_check_task_hints() {
+        let mut task_with_hints = fibonacci_with_hint_task(CUSTOM_CLAIM_INDEX_HINT);
+        task_with_hints.add_hint(
+            CUSTOM_CLAIM_INDEX_HINT.to_string(),
+            Rc::new(HintFunc(Box::new(|_, _, _, _, _| Ok(())))),
+        );
+        let tasks = vec![
+            TaskSpec::RunProgram(task_with_hints),
+            // Program input hints are implemented by `BootloaderHintProcessor`.
+            TaskSpec::RunProgram(fibonacci_with_hint_task(FIBONACCI_CLAIM_INDEX_HINT)),
+            TaskSpec::RunProgram(fibonacci_with_hint_task(CUSTOM_CLAIM_INDEX_HINT)),
+        ];
+
+        let result = check_task_hints(&tasks, &BootloaderHintProcessor::new());
+        let Err(UnsupportedHintsError(unsupported_hints)) = result else {
+            panic!("Expected unsupported hints");
+        };
+        assert_eq!(unsupported_hints.len(), 1);
+        assert_eq!(unsupported_hints[0].task_index, 2);
+        assert_matches!(
+            unsupported_hints[0].hints.as_slice(),
+            [UnsupportedHint { code, compile_error: None, .. }]
+                if code.as_str() == CUSTOM_CLAIM_INDEX_HINT
+        );
+    }
+}
@@ -0,0 +1,420 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintProcessorData;
+use cairo_vm::hint_processor::builtin_hint_processor::hint_utils::insert_value_from_var_name;
+use cairo_vm::hint_processor::hint_processor_definition::{HintProcessorLogic, HintReference};
+use cairo_vm::serde::deserialize_program::ApTracking;
+use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::types::relocatable::MaybeRelocatable;
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::runners::cairo_runner::ResourceTracker;
+use cairo_vm::vm::vm_core::VirtualMachine;
+use cairo_vm::Felt252;
+use serde_json::value::RawValue;
+
+use crate::hints::bootloader_hints::gen_arg;
+use crate::hints::vars;
+
+/// Input of a program task, available to its hints as `program_input`.
+pub type ProgramInput = HashMap<String, serde_json::Value>;
+
+/// Parses the JSON object of a program input.
+///
+/// JSON numbers are limited to 64 bits by `serde_json`, while felts go up to 252 bits: the
+/// integers that do not fit are kept as decimal strings, which the program input hints read as
+/// felts.
+pub fn parse_program_input(json: &str) -> Result<ProgramInput, serde_json::Error> {
+    let raw_input: HashMap<String, &RawValue> = serde_json::from_str(json)?;
+    raw_input
+        .into_iter()
+        .map(|(key, raw_value)| Ok((key, parse_json_value(raw_value)?)))
+        .collect()
+}
+
+fn parse_json_value(raw_value: &RawValue) -> Result<serde_json::Value, serde_json::Error> {
+    let json = raw_value.get();
+    if json.starts_with('[') {
+        let raw_items: Vec<&RawValue> = serde_json::from_str(json)?;
+        return raw_items
+            .into_iter()
+            .map(parse_json_value)
+            .collect::<Result<_, _>>()
+            .map(serde_json::Value::Array);
+    }
+    if json.starts_with('{') {
+        let raw_fields: HashMap<String, &RawValue> = serde_json::from_str(json)?;
+        return raw_fields
+            .into_iter()
+            .map(|(key, raw_value)| Ok((key, parse_json_value(raw_value)?)))
+            .collect::<Result<_, _>>()
+            .map(serde_json::Value::Object);
+    }
+
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let digits = json.strip_prefix('-').unwrap_or(json);
+    let is_integer = !digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit());
+    if is_integer && !value.is_u64() && !value.is_i64() {
+        return Ok(serde_json::Value::String(json.to_string()));
+    }
+    Ok(value)
+}
+
+/// Implementation of a hint reading the input of the program task being executed.
+#[allow(clippy::type_complexity)]
+pub struct ProgramInputHintFunc(
+    pub  Box<
+        dyn Fn(
+            &mut VirtualMachine,
+            &ProgramInput,
+            &HashMap<String, HintReference>,
+            &ApTracking,
+        ) -> Result<(), HintError>,
+    >,
+);
+
+/// How the value of a program input is written to a Cairo variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ProgramInputValue {
+    /// `program_input['key']`: the value as a felt.
+    Felt,
+    /// `len(program_input['key'])`: the length of a list.
+    Len,
+    /// `segments.gen_arg(program_input['key'])`: a pointer to a new segment holding a list.
+    GenArg,
+}
+
+/// A line of the form `ids.<var_name> = <value of program_input['<key>']>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProgramInputAssignment {
+    var_name: String,
+    key: String,
+    value: ProgramInputValue,
+}
+
+impl ProgramInputAssignment {
+    fn parse(line: &str) -> Option<Self> {
+        let (target, expression) = line.split_once('=')?;
+        let var_name = target.trim().strip_prefix("ids.")?;
+        if var_name.is_empty() || !var_name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+
+        let expression = expression.trim();
+        let (value, expression) = if let Some(inner) = strip_call(expression, "len") {
+            (ProgramInputValue::Len, inner)
+        } else if let Some(inner) = strip_call(expression, "segments.gen_arg") {
+            (ProgramInputValue::GenArg, inner)
+        } else {
+            (ProgramInputValue::Felt, expression)
+        };
+
+        let key = expression
+            .strip_prefix("program_input[")?
+            .strip_suffix(']')?
+            .trim();
+        let key = key
+            .strip_prefix('\'')
+            .and_then(|key| key.strip_suffix('\''))
+            .or_else(|| key.strip_prefix('"').and_then(|key| key.strip_suffix('"')))?;
+
+        Some(Self {
+            var_name: var_name.to_string(),
+            key: key.to_string(),
+            value,
+        })
+    }
+
+    fn execute(
+        &self,
+        vm: &mut VirtualMachine,
+        program_input: &ProgramInput,
+        ids_data: &HashMap<String, HintReference>,
+        ap_tracking: &ApTracking,
+    ) -> Result<(), HintError> {
+        let input = program_input.get(&self.key).ok_or_else(|| {
+            HintError::CustomHint(format!("Missing program input '{}'", self.key).into_boxed_str())
+        })?;
+        let value: MaybeRelocatable = match self.value {
+            ProgramInputValue::Felt => json_to_felt(&self.key, input)?.into(),
+            ProgramInputValue::Len => Felt252::from(json_to_list(&self.key, input)?.len()).into(),
+            ProgramInputValue::GenArg => {
+                let args = json_to_gen_arg_args(&self.key, json_to_list(&self.key, input)?)?;
+                gen_arg(vm, &args)?.into()
+            }
+        };
+        insert_value_from_var_name(&self.var_name, value, vm, ids_data, ap_tracking)
+    }
+}
+
+/// Returns the argument of `expression` if it is a call to `function`.
+fn strip_call<'a>(expression: &'a str, function: &str) -> Option<&'a str> {
+    expression
+        .strip_prefix(function)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+        .map(str::trim)
+}
+
+fn invalid_program_input(key: &str, expected: &str) -> HintError {
+    HintError::CustomHint(format!("Program input '{key}' is not {expected}").into_boxed_str())
+}
+
+/// Converts a JSON number, boolean or string (decimal or `0x`-prefixed hexadecimal) to a felt.
+fn json_to_felt(key: &str, value: &serde_json::Value) -> Result<Felt252, HintError> {
+    let felt = match value {
+        serde_json::Value::Number(number) => number
+            .as_u64()
+            .map(Felt252::from)
+            .or_else(|| number.as_i64().map(Felt252::from)),
+        serde_json::Value::Bool(value) => Some(Felt252::from(*value as u64)),
+        serde_json::Value::String(value) if value.starts_with("0x") => {
+            Felt252::from_hex(value).ok()
+        }
+        serde_json::Value::String(value) => Felt252::from_dec_str(value).ok(),
+        _ => None,
+    };
+    felt.ok_or_else(|| invalid_program_input(key, "a felt"))
+}
+
+fn json_to_list<'a>(
+    key: &str,
+    value: &'a serde_json::Value,
+) -> Result<&'a Vec<serde_json::Value>, HintError> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid_program_input(key, "a list"))
+}
+
+/// Converts a possibly nested list of felts to the arguments of `gen_arg`.
+fn json_to_gen_arg_args(
+    key: &str,
+    list: &[serde_json::Value],
+) -> Result<Vec<Box<dyn Any>>, HintError> {
+    list.iter()
+        .map(|item| -> Result<Box<dyn Any>, HintError> {
+            match item.as_array() {
+                Some(nested_list) => Ok(Box::new(json_to_gen_arg_args(key, nested_list)?)),
+                None => Ok(Box::new(MaybeRelocatable::from(json_to_felt(key, item)?))),
+            }
+        })
+        .collect()
+}
+
+/// Hints reading the input of the program task being executed, stored in the task scope as
+/// `program_input` by the bootloader.
+///
+/// Besides the hints added with `insert`, hints whose lines all follow one of these patterns are
+/// implemented:
+/// * `ids.x = program_input['x']`, for a number or a decimal/hexadecimal string,
+/// * `ids.n = len(program_input['x'])`, for a list,
+/// * `ids.x = segments.gen_arg(program_input['x'])`, for a possibly nested list of felts.
+#[derive(Default, Clone)]
+pub(crate) struct ProgramInputHints(HashMap<String, Rc<ProgramInputHintFunc>>);
+
+impl ProgramInputHints {
+    pub(crate) fn insert(&mut self, hint_code: String, hint_func: Rc<ProgramInputHintFunc>) {
+        self.0.insert(hint_code, hint_func);
+    }
+
+    /// Whether the hint reads the program input.
+    pub(crate) fn implements(&self, hint_code: &str) -> bool {
+        self.0.contains_key(hint_code) || parse_program_input_hint(hint_code).is_some()
+    }
+
+    /// Executes the hint, returns `HintError::UnknownHint` if it does not read the program input.
+    pub(crate) fn execute(
+        &self,
+        vm: &mut VirtualMachine,
+        exec_scopes: &ExecutionScopes,
+        hint_data: &HintProcessorData,
+    ) -> Result<(), HintError> {
+        let hint_code = hint_data.code.as_str();
+        if let Some(hint_func) = self.0.get(hint_code) {
+            let program_input: &ProgramInput = exec_scopes.get_ref(vars::PROGRAM_INPUT)?;
+            return (hint_func.0)(
+                vm,
+                program_input,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+            );
+        }
+
+        let Some(assignments) = parse_program_input_hint(hint_code) else {
+            return Err(HintError::UnknownHint(
+                hint_code.to_string().into_boxed_str(),
+            ));
+        };
+        let program_input: &ProgramInput = exec_scopes.get_ref(vars::PROGRAM_INPUT)?;
+        for assignment in &assignments {
+            assignment.execute(
+                vm,
+                program_input,
+                &hint_data.ids_data,
+                &hint_data.ap_tracking,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl HintProcessorLogic for ProgramInputHints {
+    fn execute_hint(
+        &mut self,
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data: &Box<dyn Any>,
+        _constants: &HashMap<String, Felt252>,
+    ) -> Result<(), HintError> {
+        let hint_data = hint_data
+            .downcast_ref::<HintProcessorData>()
+            .ok_or(HintError::WrongHintData)?;
+        self.execute(vm, exec_scopes, hint_data)
+    }
+}
+
+impl ResourceTracker for ProgramInputHints {}
+
+fn parse_program_input_hint(hint_code: &str) -> Option<Vec<ProgramInputAssignment>> {
+    let assignments = hint_code
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(ProgramInputAssignment::parse)
+        .collect::<Option<Vec<_>>>()?;
+    (!assignments.is_empty()).then_some(assignments)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use cairo_vm::hint_processor::builtin_hint_processor::hint_utils::get_integer_from_var_name;
+    use cairo_vm::types::relocatable::Relocatable;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{add_segments, ids_data, vm};
+
+    #[rstest]
+    #[case::felt(
+        "ids.x = program_input['x']",
+        Some(("x", "x", ProgramInputValue::Felt))
+    )]
+    #[case::double_quotes(
+        "ids.n_items = len(program_input[\"items\"])",
+        Some(("n_items", "items", ProgramInputValue::Len))
+    )]
+    #[case::gen_arg(
+        "ids.items = segments.gen_arg(program_input['items'])",
+        Some(("items", "items", ProgramInputValue::GenArg))
+    )]
+    #[case::not_ids("x = program_input['x']", None)]
+    #[case::other_expression("ids.x = compute(program_input['x'])", None)]
+    #[case::unquoted_key("ids.x = program_input[x]", None)]
+    fn test_parse_program_input_assignment(
+        #[case] line: &str,
+        #[case] expected: Option<(&str, &str, ProgramInputValue)>,
+    ) {
+        let expected = expected.map(|(var_name, key, value)| ProgramInputAssignment {
+            var_name: var_name.to_string(),
+            key: key.to_string(),
+            value,
+        });
+        assert_eq!(ProgramInputAssignment::parse(line), expected);
+    }
+
+    #[test]
+    fn test_execute_program_input_hint() {
+        let mut vm = vm!();
+        vm.set_fp(3);
+        add_segments!(vm, 2);
+        let ids_data = ids_data!["x", "n_items", "items"];
+        let hint_data = HintProcessorData::new_default(
+            "ids.x = program_input['x']\n\
+             ids.n_items = len(program_input['items'])\n\
+             ids.items = segments.gen_arg(program_input['items'])"
+                .to_string(),
+            ids_data.clone(),
+        );
+
+        let mut exec_scopes = ExecutionScopes::new();
+        let program_input: ProgramInput = HashMap::from([
+            ("x".to_string(), serde_json::json!("0x10")),
+            ("items".to_string(), serde_json::json!([1, 2, 3])),
+        ]);
+        exec_scopes.insert_value(vars::PROGRAM_INPUT, program_input);
+
+        ProgramInputHints::default()
+            .execute(&mut vm, &exec_scopes, &hint_data)
+            .expect("Hint failed unexpectedly");
+
+        let ap_tracking = ApTracking::new();
+        let x = get_integer_from_var_name("x", &vm, &ids_data, &ap_tracking).unwrap();
+        assert_eq!(x, Felt252::from(16));
+        let n_items = get_integer_from_var_name("n_items", &vm, &ids_data, &ap_tracking).unwrap();
+        assert_eq!(n_items, Felt252::from(3));
+        let items = vm.get_relocatable(Relocatable::from((1, 2))).unwrap();
+        assert_eq!(items, Relocatable::from((2, 0)));
+        let values: Vec<Felt252> = vm
+            .get_integer_range(items, 3)
+            .unwrap()
+            .into_iter()
+            .map(|value| *value)
+            .collect();
+        assert_eq!(values, [1, 2, 3].map(Felt252::from));
+    }
+
+    #[test]
+    fn test_missing_program_input() {
+        let mut vm = vm!();
+        vm.set_fp(1);
+        add_segments!(vm, 2);
+        let hint_data = HintProcessorData::new_default(
+            "ids.x = program_input['x']".to_string(),
+            ids_data!["x"],
+        );
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value(vars::PROGRAM_INPUT, ProgramInput::new());
+
+        let result = ProgramInputHints::default().execute(&mut vm, &exec_scopes, &hint_data);
+        assert_matches!(result, Err(HintError::CustomHint(message)) if message.contains("'x'"));
+    }
+
+    #[test]
+    fn test_parse_program_input() {
+        let program_input = parse_program_input(
+            r#"{
+                "small": 7,
+                "negative": -1,
+                "above_u64": 18446744073709551616,
+                "items": [1, 340282366920938463463374607431768211456],
+                "nested": {"x": -18446744073709551617, "y": 1.5},
+                "hex": "0x10"
+            }"#,
+        )
+        .expect("Failed to parse program input");
+
+        let felt = |value: &serde_json::Value| json_to_felt("x", value).unwrap();
+        assert_eq!(program_input["small"], serde_json::json!(7));
+        assert_eq!(felt(&program_input["negative"]), Felt252::from(-1));
+        assert_eq!(
+            felt(&program_input["above_u64"]),
+            Felt252::from(u64::MAX) + 1
+        );
+        let items: Vec<Felt252> = program_input["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(felt)
+            .collect();
+        assert_eq!(items, [Felt252::from(1), Felt252::from(u128::MAX) + 1]);
+        assert_eq!(
+            felt(&program_input["nested"]["x"]),
+            -(Felt252::from(u64::MAX) + 2)
+        );
+        assert_eq!(program_input["nested"]["y"], serde_json::json!(1.5));
+        assert_eq!(felt(&program_input["hex"]), Felt252::from(16));
+    }
+}
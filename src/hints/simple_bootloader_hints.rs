@@ -1,8 +1,9 @@
 use crate::hints::execute_task_hints::ALL_BUILTINS;
 use crate::hints::fact_topologies::FactTopology;
-use crate::hints::types::{RunProgramTask, SimpleBootloaderInput, TaskSpec};
+use crate::hints::types::{Cairo1ProgramTask, RunProgramTask, SimpleBootloaderInput, TaskSpec};
 use crate::hints::vars;
-use crate::CairoPieTask;
+use crate::tasks::BootloaderTaskError;
+use crate::{CairoPieTask, Error};
 use cairo_vm::hint_processor::builtin_hint_processor::hint_utils::{
     get_integer_from_var_name, get_ptr_from_var_name, insert_value_from_var_name,
     insert_value_into_ap,
@@ -131,6 +132,8 @@ pub fn set_ap_to_zero_or_one(
         Ok(task) => {
             if let Some(run_program_task) = task.as_any().downcast_ref::<RunProgramTask>() {
                 run_program_task.use_poseidon
+            } else if let Some(cairo1_task) = task.as_any().downcast_ref::<Cairo1ProgramTask>() {
+                cairo1_task.use_poseidon
             } else {
                 false
             }
@@ -163,19 +166,17 @@ pub fn set_current_task(
     let task_id = simple_bootloader_input.tasks.len() - n_tasks;
     // TODO: it's still unclear how we need to model TaskSpec/Task objects.
     //       Check if we need to keep TaskSpec, or if it needs to be implemented as a trait, etc.
-    let task = simple_bootloader_input.tasks[task_id].load_task();
-    match task {
-        Ok(task) => {
-            if let Some(run_program_task) = task.as_any().downcast_ref::<RunProgramTask>() {
-                exec_scopes
-                    .insert_value(vars::TASK, TaskSpec::RunProgram(run_program_task.clone()));
-            } else if let Some(cairo_pie_task) = task.as_any().downcast_ref::<CairoPieTask>() {
-                exec_scopes
-                    .insert_value(vars::TASK, TaskSpec::CairoPieTask(cairo_pie_task.clone()));
-            }
-        }
-        Err(_) => return Err(HintError::CustomHint("Task not found".into())),
+    let task = simple_bootloader_input.tasks[task_id]
+        .load_task()
+        .map_err(|e| Error::from(BootloaderTaskError::from(e)))?;
+    if let Some(run_program_task) = task.as_any().downcast_ref::<RunProgramTask>() {
+        exec_scopes.insert_value(vars::TASK, TaskSpec::RunProgram(run_program_task.clone()));
+    } else if let Some(cairo1_task) = task.as_any().downcast_ref::<Cairo1ProgramTask>() {
+        exec_scopes.insert_value(vars::TASK, TaskSpec::RunCairo1Program(cairo1_task.clone()));
+    } else if let Some(cairo_pie_task) = task.as_any().downcast_ref::<CairoPieTask>() {
+        exec_scopes.insert_value(vars::TASK, TaskSpec::CairoPieTask(cairo_pie_task.clone()));
     }
+    exec_scopes.insert_value(vars::TASK_ID, task_id);
 
     Ok(HashMap::new())
 }
@@ -202,7 +203,7 @@ mod tests {
 
     use crate::hints::fact_topologies::FactTopology;
 
-    use crate::hints::types::TaskSpec;
+    use crate::hints::types::{TaskHints, TaskSpec};
     use crate::hints::vars;
     use crate::{add_segments, define_segments, ids_data, vm};
 
@@ -228,11 +229,15 @@ mod tests {
                     program: fibonacci.clone(),
                     program_input: HashMap::new(),
                     use_poseidon: true,
+                    hints: TaskHints::default(),
+                    max_steps: None,
                 }),
                 TaskSpec::RunProgram(RunProgramTask {
                     program: fibonacci.clone(),
                     program_input: HashMap::new(),
                     use_poseidon: true,
+                    hints: TaskHints::default(),
+                    max_steps: None,
                 }),
             ],
         }
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use crate::hints::task_context::TaskContext;
+
+/// A step limit exceeded during a bootloader run.
+///
+/// The hint processor stops the VM through `ResourceTracker::consumed`, the run then fails with
+/// `VirtualMachineError::UnfinishedExecution` and this error is available from the hint processor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepLimitExceeded {
+    /// A task exceeded the limit set in its `TaskSpec`.
+    Task { task: TaskContext, max_steps: usize },
+    /// The run exceeded its overall limit, while executing `task` if it was not in the
+    /// bootloader code.
+    Run {
+        task: Option<TaskContext>,
+        max_steps: usize,
+    },
+}
+
+impl fmt::Display for StepLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepLimitExceeded::Task { task, max_steps } => {
+                write!(
+                    f,
+                    "Step limit exceeded: {task} ran more than {max_steps} steps"
+                )
+            }
+            StepLimitExceeded::Run {
+                task: Some(task),
+                max_steps,
+            } => write!(
+                f,
+                "Step limit exceeded: the run reached {max_steps} steps while executing {task}"
+            ),
+            StepLimitExceeded::Run {
+                task: None,
+                max_steps,
+            } => write!(f, "Step limit exceeded: the run reached {max_steps} steps"),
+        }
+    }
+}
+
+impl std::error::Error for StepLimitExceeded {}
+
+struct RunningTaskBudget {
+    task: TaskContext,
+    start_step: usize,
+    max_steps: Option<usize>,
+}
+
+/// Checks the step limits of a run, step by step.
+#[derive(Default)]
+pub(crate) struct StepLimiter {
+    /// Overall limit of the run, `None` for no limit.
+    max_steps: Option<usize>,
+    /// The tasks being executed, the innermost task last. Every called task pushes an entry,
+    /// `None` if its index is unknown, so that leaving its scope pops the right one.
+    running_tasks: Vec<Option<RunningTaskBudget>>,
+    exceeded: Option<StepLimitExceeded>,
+}
+
+impl StepLimiter {
+    pub(crate) fn set_max_steps(&mut self, max_steps: Option<usize>) {
+        self.max_steps = max_steps;
+    }
+
+    pub(crate) fn enter_task(
+        &mut self,
+        task: Option<TaskContext>,
+        max_steps: Option<usize>,
+        n_steps: usize,
+    ) {
+        self.running_tasks.push(task.map(|task| RunningTaskBudget {
+            task,
+            start_step: n_steps,
+            max_steps,
+        }));
+    }
+
+    pub(crate) fn exit_task(&mut self) {
+        self.running_tasks.pop();
+    }
+
+    /// Records the first limit exceeded after `n_steps` steps.
+    pub(crate) fn check(&mut self, n_steps: usize) {
+        if self.exceeded.is_some() {
+            return;
+        }
+
+        if let Some(max_steps) = self.max_steps.filter(|max_steps| n_steps > *max_steps) {
+            self.exceeded = Some(StepLimitExceeded::Run {
+                task: self
+                    .running_tasks
+                    .first()
+                    .and_then(|budget| budget.as_ref().map(|budget| budget.task.clone())),
+                max_steps,
+            });
+            return;
+        }
+
+        self.exceeded = self.running_tasks.iter().flatten().find_map(|budget| {
+            budget
+                .max_steps
+                .filter(|max_steps| n_steps - budget.start_step > *max_steps)
+                .map(|max_steps| StepLimitExceeded::Task {
+                    task: budget.task.clone(),
+                    max_steps,
+                })
+        });
+    }
+
+    pub(crate) fn exceeded(&self) -> Option<&StepLimitExceeded> {
+        self.exceeded.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_vm::Felt252;
+
+    use super::*;
+    use crate::hints::task_context::TaskKind;
+
+    fn task(task_index: usize) -> Option<TaskContext> {
+        Some(TaskContext {
+            task_index,
+            kind: TaskKind::Program,
+            source_path: None,
+            program_hash: Some(Felt252::from(0xabc)),
+        })
+    }
+
+    #[test]
+    fn test_task_step_limit() {
+        let mut step_limiter = StepLimiter::default();
+        step_limiter.enter_task(task(0), Some(10), 5);
+        step_limiter.check(15);
+        assert_eq!(step_limiter.exceeded(), None);
+        step_limiter.exit_task();
+
+        step_limiter.enter_task(task(1), Some(10), 20);
+        step_limiter.check(31);
+        assert_eq!(
+            step_limiter.exceeded(),
+            Some(&StepLimitExceeded::Task {
+                task: task(1).unwrap(),
+                max_steps: 10
+            })
+        );
+        assert_eq!(
+            step_limiter.exceeded().unwrap().to_string(),
+            "Step limit exceeded: task 1 (program, program hash 0xabc) ran more than 10 steps"
+        );
+    }
+
+    #[test]
+    fn test_run_step_limit() {
+        let mut step_limiter = StepLimiter::default();
+        step_limiter.set_max_steps(Some(100));
+        step_limiter.enter_task(task(2), None, 50);
+        step_limiter.check(101);
+        assert_eq!(
+            step_limiter.exceeded(),
+            Some(&StepLimitExceeded::Run {
+                task: task(2),
+                max_steps: 100
+            })
+        );
+    }
+}
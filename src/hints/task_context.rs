@@ -0,0 +1,109 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
+use cairo_vm::Felt252;
+
+use crate::hints::types::TaskSpec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    Program,
+    Cairo1Program,
+    CairoPie,
+}
+
+impl fmt::Display for TaskKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskKind::Program => write!(f, "program"),
+            TaskKind::Cairo1Program => write!(f, "Cairo 1 program"),
+            TaskKind::CairoPie => write!(f, "Cairo PIE"),
+        }
+    }
+}
+
+/// The task being executed by the bootloader, as named in errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskContext {
+    /// Index of the task in the simple bootloader input.
+    pub task_index: usize,
+    pub kind: TaskKind,
+    /// File the task was read from, if known.
+    pub source_path: Option<PathBuf>,
+    /// Hash of the task program, `None` until the bootloader hashed it, just before calling the
+    /// task.
+    pub program_hash: Option<Felt252>,
+}
+
+impl TaskContext {
+    pub(crate) fn new(task_index: usize, task: &TaskSpec) -> Self {
+        let (kind, source_path) = match task {
+            TaskSpec::RunProgram(_) => (TaskKind::Program, None),
+            TaskSpec::RunCairo1Program(_) => (TaskKind::Cairo1Program, None),
+            TaskSpec::CairoPiePath(task) => (TaskKind::CairoPie, Some(task.path.clone())),
+            TaskSpec::CairoPieBytes(_) => (TaskKind::CairoPie, None),
+            TaskSpec::CairoPieTask(task) => (TaskKind::CairoPie, task.source_path.clone()),
+        };
+        Self {
+            task_index,
+            kind,
+            source_path,
+            program_hash: None,
+        }
+    }
+}
+
+impl fmt::Display for TaskContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task {} ({}", self.task_index, self.kind)?;
+        if let Some(source_path) = &self.source_path {
+            write!(f, " {}", source_path.display())?;
+        }
+        if let Some(program_hash) = self.program_hash {
+            write!(f, ", program hash {program_hash:#x}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A bootloader run that failed while executing one of its tasks.
+#[derive(thiserror_no_std::Error, Debug)]
+#[error("Failed to execute {task}: {source}")]
+pub struct TaskExecutionError {
+    pub task: TaskContext,
+    pub source: CairoRunError,
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::pie_path(
+        Some(PathBuf::from("fibonacci.zip")),
+        Some(Felt252::from(0xabc)),
+        "task 2 (Cairo PIE fibonacci.zip, program hash 0xabc)"
+    )]
+    #[case::hash_only(
+        None,
+        Some(Felt252::from(0xabc)),
+        "task 2 (Cairo PIE, program hash 0xabc)"
+    )]
+    #[case::before_hash_validation(None, None, "task 2 (Cairo PIE)")]
+    fn test_task_context_display(
+        #[case] source_path: Option<PathBuf>,
+        #[case] program_hash: Option<Felt252>,
+        #[case] expected: &str,
+    ) {
+        let task = TaskContext {
+            task_index: 2,
+            kind: TaskKind::CairoPie,
+            source_path,
+            program_hash,
+        };
+        assert_eq!(task.to_string(), expected);
+    }
+}
@@ -1,7 +1,12 @@
 use std::any::Any;
 use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
 use std::path::PathBuf;
+use std::rc::Rc;
 
+use cairo_lang_casm::hints::Hint;
+use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintFunc;
 use cairo_vm::serde::deserialize_program::Identifier;
 use cairo_vm::types::errors::program_errors::ProgramError;
 use cairo_vm::types::program::Program;
@@ -9,6 +14,8 @@ use cairo_vm::vm::runners::cairo_pie::CairoPie;
 use cairo_vm::Felt252;
 use serde::Deserialize;
 
+use crate::hints::fact_topologies::{FactTopology, FactTopologyError};
+
 pub type BootloaderVersion = u64;
 
 pub(crate) type ProgramIdentifiers = HashMap<String, Identifier>;
@@ -21,14 +28,65 @@ pub struct BootloaderConfig {
 
 #[derive(Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct CompositePackedOutput {
+    /// Output of the simple bootloader run that executed the subtasks, as hashed by the verifier.
     pub outputs: Vec<Felt252>,
     pub subtasks: Vec<PackedOutput>,
+    /// Fact topologies of the subtasks, in the same order as `subtasks`. Missing from inputs
+    /// written before they were recorded.
+    #[serde(default)]
+    pub fact_topologies: Vec<FactTopology>,
 }
 
 impl CompositePackedOutput {
+    pub fn new(
+        outputs: Vec<Felt252>,
+        subtasks: Vec<PackedOutput>,
+        fact_topologies: Vec<FactTopology>,
+    ) -> Self {
+        Self {
+            outputs,
+            subtasks,
+            fact_topologies,
+        }
+    }
+
     pub fn elements_for_hash(&self) -> &Vec<Felt252> {
         &self.outputs
     }
+
+    /// Returns the fact topologies of the plain tasks in this subtree, in unpacking order.
+    ///
+    /// Reimplements the following Python code:
+    /// ```no-run
+    /// subtasks_fact_topologies = []
+    /// assert len(self.subtasks) == len(self.fact_topologies)
+    /// for subtask, fact_topology in zip(self.subtasks, self.fact_topologies):
+    ///     if isinstance(subtask, PlainPackedOutput):
+    ///         subtasks_fact_topologies.append(fact_topology)
+    ///     else:
+    ///         subtasks_fact_topologies += subtask.get_plain_fact_topologies()
+    /// return subtasks_fact_topologies
+    /// ```
+    pub fn get_plain_fact_topologies(&self) -> Result<Vec<&FactTopology>, FactTopologyError> {
+        if self.subtasks.len() != self.fact_topologies.len() {
+            return Err(FactTopologyError::WrongNumberOfFactTopologies(
+                self.subtasks.len(),
+                self.fact_topologies.len(),
+            ));
+        }
+
+        let mut plain_fact_topologies = vec![];
+        for (subtask, fact_topology) in std::iter::zip(&self.subtasks, &self.fact_topologies) {
+            match subtask {
+                PackedOutput::Plain(_) => plain_fact_topologies.push(fact_topology),
+                PackedOutput::Composite(composite) => {
+                    plain_fact_topologies.extend(composite.get_plain_fact_topologies()?)
+                }
+            }
+        }
+
+        Ok(plain_fact_topologies)
+    }
 }
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -45,7 +103,9 @@ pub trait Task {
 #[derive(Debug, Clone, PartialEq)]
 pub enum TaskSpec {
     RunProgram(RunProgramTask),
+    RunCairo1Program(Cairo1ProgramTask),
     CairoPiePath(CairoPiePath),
+    CairoPieBytes(CairoPieBytes),
     CairoPieTask(CairoPieTask),
 }
 
@@ -53,23 +113,109 @@ impl TaskSpec {
     pub fn load_task(&self) -> Result<Box<dyn Task>, std::io::Error> {
         match self {
             TaskSpec::RunProgram(task) => Ok(Box::new(task.clone())),
+            TaskSpec::RunCairo1Program(task) => Ok(Box::new(task.clone())),
             TaskSpec::CairoPiePath(path) => {
                 let cairo_pie = CairoPie::read_zip_file(&path.path)?;
                 Ok(Box::new(CairoPieTask {
                     cairo_pie,
                     use_poseidon: path.use_poseidon,
+                    source_path: Some(path.path.clone()),
+                    max_steps: path.max_steps,
+                }))
+            }
+            TaskSpec::CairoPieBytes(pie) => {
+                let cairo_pie = CairoPie::from_bytes(&pie.bytes)?;
+                Ok(Box::new(CairoPieTask {
+                    cairo_pie,
+                    use_poseidon: pie.use_poseidon,
+                    source_path: None,
+                    max_steps: pie.max_steps,
                 }))
             }
             TaskSpec::CairoPieTask(task) => Ok(Box::new(task.clone())),
         }
     }
+
+    /// Creates a PIE task spec from the content of a PIE zip file.
+    ///
+    /// The reader is consumed immediately, but the PIE itself is only decoded in `load_task`.
+    pub fn from_pie_reader<R: Read>(reader: R, use_poseidon: bool) -> std::io::Result<Self> {
+        CairoPieBytes::from_reader(reader, use_poseidon).map(TaskSpec::CairoPieBytes)
+    }
+
+    /// Maximum number of VM steps of the task, `None` for no limit.
+    pub fn max_steps(&self) -> Option<usize> {
+        match self {
+            TaskSpec::RunProgram(task) => task.max_steps,
+            TaskSpec::RunCairo1Program(task) => task.max_steps,
+            TaskSpec::CairoPiePath(task) => task.max_steps,
+            TaskSpec::CairoPieBytes(task) => task.max_steps,
+            TaskSpec::CairoPieTask(task) => task.max_steps,
+        }
+    }
+
+    pub fn set_max_steps(&mut self, max_steps: Option<usize>) {
+        match self {
+            TaskSpec::RunProgram(task) => task.max_steps = max_steps,
+            TaskSpec::RunCairo1Program(task) => task.max_steps = max_steps,
+            TaskSpec::CairoPiePath(task) => task.max_steps = max_steps,
+            TaskSpec::CairoPieBytes(task) => task.max_steps = max_steps,
+            TaskSpec::CairoPieTask(task) => task.max_steps = max_steps,
+        }
+    }
+}
+
+/// Hint implementations available only while the task that carries them runs.
+#[derive(Clone, Default)]
+pub struct TaskHints(HashMap<String, Rc<HintFunc>>);
+
+impl TaskHints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, hint_code: String, hint_func: Rc<HintFunc>) {
+        self.0.insert(hint_code, hint_func);
+    }
+
+    pub fn get(&self, hint_code: &str) -> Option<&Rc<HintFunc>> {
+        self.0.get(hint_code)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for TaskHints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+/// Hint functions cannot be compared, two tables are equal if they share the same functions.
+impl PartialEq for TaskHints {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self.0.iter().all(|(hint_code, hint_func)| {
+                other
+                    .0
+                    .get(hint_code)
+                    .is_some_and(|other_func| Rc::ptr_eq(hint_func, other_func))
+            })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct RunProgramTask {
     pub program: Program,
     pub program_input: HashMap<String, serde_json::Value>,
     pub use_poseidon: bool,
+    /// Hints of the program that the bootloader hint processors do not implement.
+    pub hints: TaskHints,
+    /// Maximum number of VM steps of the task, `None` for no limit.
+    pub max_steps: Option<usize>,
 }
 
 impl Task for RunProgramTask {
@@ -92,20 +238,96 @@ impl RunProgramTask {
             program,
             program_input,
             use_poseidon,
+            hints: TaskHints::new(),
+            max_steps: None,
         }
     }
+
+    /// Adds a hint implementation used only while this task runs.
+    pub fn add_hint(&mut self, hint_code: String, hint_func: Rc<HintFunc>) {
+        self.hints.insert(hint_code, hint_func);
+    }
+}
+
+/// A Cairo 1 executable run from its bootloader entry point, see `Cairo1ProgramTask::from_executable`.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Cairo1ProgramTask {
+    pub program: Program,
+    /// Cairo 1 hints of the program, indexed by their offset in the bytecode.
+    pub hints: Vec<(usize, Vec<Hint>)>,
+    /// Arguments of the executable function, serialized as felts.
+    pub args: Vec<Felt252>,
+    pub use_poseidon: bool,
+    /// Maximum number of VM steps of the task, `None` for no limit.
+    pub max_steps: Option<usize>,
+}
+
+impl Task for Cairo1ProgramTask {
+    fn get_program(&self) -> Result<Program, ProgramError> {
+        Ok(self.program.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct CairoPiePath {
     pub path: PathBuf,
     pub use_poseidon: bool,
+    /// Maximum number of VM steps of the task, `None` for no limit.
+    pub max_steps: Option<usize>,
 }
 
+/// A Cairo PIE zip file held in memory, e.g. received over the network.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct CairoPieBytes {
+    pub bytes: Vec<u8>,
+    pub use_poseidon: bool,
+    /// Maximum number of VM steps of the task, `None` for no limit.
+    pub max_steps: Option<usize>,
+}
+
+impl CairoPiePath {
+    pub fn new(path: PathBuf, use_poseidon: bool) -> Self {
+        Self {
+            path,
+            use_poseidon,
+            max_steps: None,
+        }
+    }
+}
+
+impl CairoPieBytes {
+    pub fn new(bytes: Vec<u8>, use_poseidon: bool) -> Self {
+        Self {
+            bytes,
+            use_poseidon,
+            max_steps: None,
+        }
+    }
+
+    /// Reads the whole PIE zip file from `reader`.
+    pub fn from_reader<R: Read>(mut reader: R, use_poseidon: bool) -> std::io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Ok(Self::new(bytes, use_poseidon))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct CairoPieTask {
     pub cairo_pie: CairoPie,
     pub use_poseidon: bool,
+    /// File the PIE was read from, if any, to name the task in errors.
+    pub source_path: Option<PathBuf>,
+    /// Maximum number of VM steps of the task, `None` for no limit.
+    pub max_steps: Option<usize>,
 }
 
 impl Task for CairoPieTask {
@@ -125,6 +347,8 @@ impl CairoPieTask {
         Self {
             cairo_pie,
             use_poseidon,
+            source_path: None,
+            max_steps: None,
         }
     }
 }
@@ -147,3 +371,69 @@ pub struct BootloaderInput {
     // Setting this to true will ignore the fact_topologies and add all outputs of tasks to page 0.
     pub ignore_fact_topologies: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::path::Path;
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    const FIBONACCI_PIE: &[u8] = include_bytes!("../../examples/fibonacci_with_output.zip");
+
+    fn load_cairo_pie_task(task_spec: &TaskSpec) -> CairoPieTask {
+        let task = task_spec.load_task().expect("Failed to load task");
+        task.as_any()
+            .downcast_ref::<CairoPieTask>()
+            .expect("Expected a Cairo PIE task")
+            .clone()
+    }
+
+    #[test]
+    fn test_load_cairo_pie_from_bytes() {
+        let from_path = TaskSpec::CairoPiePath(CairoPiePath::new(
+            Path::new("examples/fibonacci_with_output.zip").to_path_buf(),
+            true,
+        ));
+        let from_bytes = TaskSpec::CairoPieBytes(CairoPieBytes::new(FIBONACCI_PIE.to_vec(), true));
+        let from_reader = TaskSpec::from_pie_reader(Cursor::new(FIBONACCI_PIE), true)
+            .expect("Failed to read PIE");
+
+        let from_path = load_cairo_pie_task(&from_path);
+        assert_eq!(
+            from_path.source_path.as_deref(),
+            Some(Path::new("examples/fibonacci_with_output.zip"))
+        );
+
+        // Only PIEs read from a file know their source.
+        let expected = CairoPieTask {
+            source_path: None,
+            ..from_path
+        };
+        assert_eq!(load_cairo_pie_task(&from_bytes), expected);
+        assert_eq!(load_cairo_pie_task(&from_reader), expected);
+    }
+
+    #[test]
+    fn test_load_cairo_pie_from_invalid_bytes() {
+        let task_spec = TaskSpec::CairoPieBytes(CairoPieBytes::new(b"not a zip".to_vec(), false));
+        assert!(task_spec.load_task().is_err());
+    }
+
+    #[test]
+    fn test_composite_packed_output_without_fact_topologies() {
+        let composite: CompositePackedOutput = serde_json::from_str(
+            r#"{"outputs": ["0x1", "0x2"], "subtasks": [{"Plain": ["0x3", "0x4"]}]}"#,
+        )
+        .expect("Failed to parse composite packed output");
+
+        assert_eq!(composite.subtasks.len(), 1);
+        assert!(composite.fact_topologies.is_empty());
+        assert_matches!(
+            composite.get_plain_fact_topologies(),
+            Err(FactTopologyError::WrongNumberOfFactTopologies(1, 0))
+        );
+    }
+}
@@ -48,3 +48,27 @@ pub const N_SELECTED_BUILTINS: &str = "n_selected_builtins";
 
 /// "pre_execution_builtin_ptrs"
 pub const PRE_EXECUTION_BUILTIN_PTRS: &str = "pre_execution_builtin_ptrs";
+
+/// Index of the current task in the simple bootloader input.
+pub const TASK_ID: &str = "task_id";
+
+/// Base of the execution segment of the current Cairo PIE task.
+pub const EXECUTION_SEGMENT_ADDRESS: &str = "execution_segment_address";
+
+/// Number of VM steps executed when calling the current task.
+pub const TASK_START_STEP: &str = "task_start_step";
+
+/// Number of VM steps executed when returning from the current task.
+pub const TASK_END_STEP: &str = "task_end_step";
+
+/// Program hash of the current task, once the bootloader hashed its program.
+pub const PROGRAM_HASH: &str = "program_hash";
+
+/// Input of the current program task, as a `ProgramInput`.
+pub const PROGRAM_INPUT: &str = "program_input";
+
+/// Set in the scope of program tasks to tell them they run in the bootloader.
+pub const WITH_BOOTLOADER: &str = "WITH_BOOTLOADER";
+
+/// Fact topologies of the plain outputs of the bootloader, once computed.
+pub const PLAIN_FACT_TOPOLOGIES: &str = "plain_fact_topologies";
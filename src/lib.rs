@@ -1,8 +1,14 @@
 use cairo_vm::types::exec_scope::ExecutionScopes;
+pub use error::Error;
 pub use hints::*;
 
+pub mod batch;
 pub mod bootloaders;
+pub mod capacity;
+pub mod error;
 pub mod hints;
+pub mod prover_input;
+pub mod run;
 pub mod tasks;
 
 #[cfg(test)]
@@ -1,186 +1,299 @@
-use bincode::enc::write::Writer;
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::fs::File;
-use std::io::Write;
 use std::path::{Path, PathBuf};
 
-use cairo_vm::air_public_input::PublicInput;
-use cairo_vm::cairo_run::{
-    cairo_run_program_with_initial_scope, write_encoded_memory, write_encoded_trace, CairoRunConfig,
-};
-use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::hint_processor::hint_processor_definition::HintProcessor;
+use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::types::layout::CairoLayoutParams;
 use cairo_vm::types::layout_name::LayoutName;
 use cairo_vm::types::program::Program;
-use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
+use cairo_vm::vm::runners::builtin_runner::OutputBuiltinRunner;
+use cairo_vm::vm::runners::cairo_pie::{BuiltinAdditionalData, CairoPie};
 use cairo_vm::vm::runners::cairo_runner::CairoRunner;
-use cairo_vm::vm::trace::trace_entry::RelocatedTraceEntry;
 use cairo_vm::Felt252;
 
+use cairo_bootloader::batch::{BatchBudget, BatchPlanner};
 use cairo_bootloader::bootloaders::load_bootloader;
+use cairo_bootloader::capacity::CapacityReport;
+use cairo_bootloader::prover_input::{write_prover_input, ProverInputFileNames, ProverInputPaths};
+use cairo_bootloader::run::{
+    decode_task_outputs, get_bootloader_output, run_bootloader_with_hint_processor, RunOptions,
+};
 use cairo_bootloader::tasks::make_bootloader_tasks;
 use cairo_bootloader::{
-    insert_bootloader_input, BootloaderConfig, BootloaderHintProcessor, BootloaderInput,
-    PackedOutput, SimpleBootloaderInput, TaskSpec,
+    check_task_hints, compute_program_hash_chain, get_task_fact_topology, BootloaderHintProcessor,
+    CairoPieTask, HintProfiler, PieResourcesCheck, Task, TaskSpec,
 };
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
-fn cairo_run_bootloader_in_proof_mode(
-    bootloader_program: &Program,
-    tasks: Vec<TaskSpec>,
-) -> Result<CairoRunner, CairoRunError> {
-    let mut hint_processor = BootloaderHintProcessor::new();
-
-    let cairo_run_config = CairoRunConfig {
-        entrypoint: "main",
-        trace_enabled: true,
-        relocate_mem: true,
-        layout: LayoutName::all_cairo_stwo,
-        proof_mode: true,
-        secure_run: None,
-        disable_trace_padding: true,
-        allow_missing_builtins: None,
-        dynamic_layout_params: None,
-        ..Default::default()
-    };
-
-    // Build the bootloader input
-    let n_tasks = tasks.len();
-    let bootloader_input = BootloaderInput {
-        simple_bootloader_input: SimpleBootloaderInput {
-            fact_topologies_path: None,
-            single_page: false,
-            tasks,
-        },
-        bootloader_config: BootloaderConfig {
-            simple_bootloader_program_hash: Felt252::from(0),
-            supported_cairo_verifier_program_hashes: vec![],
-        },
-        packed_outputs: vec![PackedOutput::Plain(vec![]); n_tasks],
-        ignore_fact_topologies: true,
-    };
-
-    let mut exec_scopes = ExecutionScopes::new();
-    insert_bootloader_input(&mut exec_scopes, bootloader_input);
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
 
-    // Run the bootloader
-    cairo_run_program_with_initial_scope(
-        bootloader_program,
-        &cairo_run_config,
-        &mut hint_processor,
-        exec_scopes,
-    )
+    #[command(flatten)]
+    prove: ProveArgs,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PrivateInput {
-    pub trace_path: PathBuf,
-    pub memory_path: PathBuf,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run the bootloader without proof mode, trace or output files and print the task outputs
+    Execute(RunArgs),
+    /// Print the metadata of a Cairo PIE
+    Inspect(InspectArgs),
 }
 
-pub struct FileWriter {
-    buf_writer: std::io::BufWriter<std::fs::File>,
-    bytes_written: usize,
+#[derive(Args, Debug)]
+struct InspectArgs {
+    /// Path to the PIE file (*.zip)
+    pie: PathBuf,
 }
 
-impl Writer for FileWriter {
-    fn write(&mut self, bytes: &[u8]) -> Result<(), bincode::error::EncodeError> {
-        self.buf_writer
-            .write_all(bytes)
-            .map_err(|e| bincode::error::EncodeError::Io {
-                inner: e,
-                index: self.bytes_written,
-            })?;
+#[derive(Args, Debug)]
+struct ProveArgs {
+    #[command(flatten)]
+    run: RunArgs,
 
-        self.bytes_written += bytes.len();
+    /// Output directory for the generated files
+    #[arg(short, long, required_unless_present = "cairo_pie_output")]
+    output_path: Option<PathBuf>,
 
-        Ok(())
-    }
+    /// Run the bootloader outside of proof mode and export its execution as a Cairo PIE (*.zip)
+    #[arg(long)]
+    cairo_pie_output: Option<PathBuf>,
+
+    /// Also write the builtin private inputs to air_private_input.json in the output directory
+    #[arg(long)]
+    air_private_input: bool,
+
+    /// Split the tasks into several bootloader runs of at most this many (estimated) steps, each
+    /// written to its own subdirectory of the output directory
+    #[arg(
+        long,
+        requires = "output_path",
+        conflicts_with_all = ["cairo_pie_output", "report"]
+    )]
+    max_steps_per_run: Option<usize>,
+
+    /// Split the tasks into several bootloader runs whose trace components (main trace and each
+    /// builtin) fit, once padded, in 2^n rows
+    #[arg(
+        long,
+        requires = "output_path",
+        conflicts_with_all = ["cairo_pie_output", "report"]
+    )]
+    max_trace_log_size_per_run: Option<u32>,
 }
 
-impl FileWriter {
-    fn new(buf_writer: std::io::BufWriter<std::fs::File>) -> Self {
-        Self {
-            buf_writer,
-            bytes_written: 0,
-        }
-    }
+/// Maps the tasks to the runs of a split batch, written to `batch_index.json`.
+#[derive(Debug, Serialize)]
+struct BatchIndex {
+    runs: Vec<BatchIndexRun>,
 }
 
-pub fn prover_input_from_runner<'r>(
-    runner: &'r CairoRunner,
-    output_dir: &Path,
-) -> (PrivateInput, PublicInput<'r>) {
-    let public_input = runner.get_air_public_input().unwrap();
-    let trace = runner
-        .relocated_trace
-        .as_ref()
-        .unwrap()
-        .iter()
-        .map(|x| RelocatedTraceEntry {
-            ap: x.ap,
-            fp: x.fp,
-            pc: x.pc,
-        })
-        .collect::<Vec<_>>();
+#[derive(Debug, Serialize)]
+struct BatchIndexRun {
+    directory: PathBuf,
+    estimated_steps: usize,
+    n_steps: usize,
+    tasks: Vec<BatchIndexTask>,
+}
 
-    let trace_path = output_dir.join("trace");
-    let trace_file = File::create(&trace_path).unwrap();
-    let mut trace_writer = FileWriter::new(std::io::BufWriter::with_capacity(
-        3 * 1024 * 1024,
-        trace_file,
-    ));
-    write_encoded_trace(&trace, &mut trace_writer).unwrap();
-
-    let memory_path = output_dir.join("memory");
-    let memory_file = File::create(&memory_path).unwrap();
-    let mut memory_writer = FileWriter::new(std::io::BufWriter::with_capacity(
-        5 * 1024 * 1024,
-        memory_file,
-    ));
-    write_encoded_memory(&runner.relocated_memory, &mut memory_writer).unwrap();
-
-    let private_input = PrivateInput {
-        trace_path: std::fs::canonicalize(&trace_path).unwrap(),
-        memory_path: std::fs::canonicalize(&memory_path).unwrap(),
-    };
-    (private_input, public_input)
+#[derive(Debug, Serialize)]
+struct BatchIndexTask {
+    index: usize,
+    pie: PathBuf,
 }
 
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
-struct Args {
+#[derive(Args, Debug)]
+struct RunArgs {
     /// Paths to the PIE files (*.zip)
     #[arg(short, long, num_args = 1..)]
     pie: Vec<PathBuf>,
 
-    /// Output directory for the generated files
-    #[arg(short, long)]
-    output_path: PathBuf,
+    /// Only warn when the execution resources recorded in a PIE do not match the bootloader run
+    #[arg(long)]
+    warn_inconsistent_pies: bool,
+
+    /// Layout used to run the bootloader
+    #[arg(long, default_value = "all_cairo_stwo", value_parser = parse_layout)]
+    layout: LayoutName,
+
+    /// Parameters of the dynamic layout (JSON), required with `--layout dynamic`
+    #[arg(long)]
+    cairo_layout_params_file: Option<PathBuf>,
+
+    /// Write a JSON report of the run (tasks, outputs, resources and generated files)
+    #[arg(long)]
+    report: Option<PathBuf>,
+
+    /// Print the calls and time spent in each hint, per task, at the end of the run
+    #[arg(long)]
+    profile_hints: bool,
+
+    /// Stop the run after this many VM steps
+    #[arg(long)]
+    max_steps: Option<usize>,
+
+    /// Stop the run when a task executes more than this many VM steps: one limit for all the
+    /// tasks, or one per PIE, in the order of `--pie`
+    #[arg(long, num_args = 1..)]
+    max_task_steps: Vec<usize>,
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
-    let bootloader_program = load_bootloader()?;
+/// Machine-readable summary of a bootloader run, written with `--report`.
+#[derive(Debug, Serialize)]
+struct RunReport {
+    version: &'static str,
+    layout: &'static str,
+    tasks: Vec<TaskReport>,
+    n_steps: usize,
+    n_memory_holes: usize,
+    builtin_instance_counter: BTreeMap<String, usize>,
+    capacity: CapacityReport,
+    artifacts: ArtifactsReport,
+}
 
-    let pie_paths: Vec<&Path> = args.pie.iter().map(|p| p.as_ref()).collect();
-    let tasks = make_bootloader_tasks(None, None, Some(&pie_paths))?;
+#[derive(Debug, Serialize)]
+struct TaskReport {
+    pie: Option<PathBuf>,
+    program_hash: Felt252,
+    output: Vec<Felt252>,
+}
 
-    let mut runner = cairo_run_bootloader_in_proof_mode(&bootloader_program, tasks)?;
+#[derive(Debug, Default, Serialize)]
+struct ArtifactsReport {
+    cairo_pie: Option<PathBuf>,
+    trace: Option<PathBuf>,
+    memory: Option<PathBuf>,
+    private_input: Option<PathBuf>,
+    public_input: Option<PathBuf>,
+    air_private_input: Option<PathBuf>,
+}
 
-    let mut output_buffer = "Program Output:\n".to_string();
-    runner.vm.write_output(&mut output_buffer)?;
-    print!("{output_buffer}");
-    println!("--------------------------------");
+const LAYOUTS: [LayoutName; 12] = [
+    LayoutName::plain,
+    LayoutName::small,
+    LayoutName::dex,
+    LayoutName::recursive,
+    LayoutName::starknet,
+    LayoutName::starknet_with_keccak,
+    LayoutName::recursive_large_output,
+    LayoutName::recursive_with_poseidon,
+    LayoutName::all_solidity,
+    LayoutName::all_cairo,
+    LayoutName::all_cairo_stwo,
+    LayoutName::dynamic,
+];
+
+fn parse_layout(name: &str) -> Result<LayoutName, String> {
+    LAYOUTS
+        .into_iter()
+        .find(|layout| layout.to_str() == name)
+        .ok_or_else(|| {
+            let names: Vec<&str> = LAYOUTS.iter().map(|layout| layout.to_str()).collect();
+            format!("unknown layout, expected one of: {}", names.join(", "))
+        })
+}
+
+fn load_dynamic_layout_params(args: &RunArgs) -> Result<Option<CairoLayoutParams>, Box<dyn Error>> {
+    match (args.layout, &args.cairo_layout_params_file) {
+        (LayoutName::dynamic, Some(path)) => Ok(Some(CairoLayoutParams::from_file(path)?)),
+        (LayoutName::dynamic, None) => {
+            Err("--cairo-layout-params-file is required with the dynamic layout".into())
+        }
+        (_, Some(_)) => {
+            Err("--cairo-layout-params-file can only be used with the dynamic layout".into())
+        }
+        (_, None) => Ok(None),
+    }
+}
+
+/// Makes sure that the layout provides every builtin declared by the bootloader before running it.
+fn check_layout_builtins(
+    bootloader_program: &Program,
+    layout: LayoutName,
+    dynamic_layout_params: Option<CairoLayoutParams>,
+) -> Result<(), Box<dyn Error>> {
+    let mut runner = CairoRunner::new(
+        bootloader_program,
+        layout,
+        dynamic_layout_params,
+        false,
+        false,
+        false,
+    )?;
+    runner
+        .initialize_builtins(false)
+        .map_err(|e| format!("layout {} cannot run the bootloader: {e}", layout.to_str()))?;
+    Ok(())
+}
+
+fn make_run_options(
+    args: &RunArgs,
+    proof_mode: bool,
+    dynamic_layout_params: Option<CairoLayoutParams>,
+) -> RunOptions {
+    RunOptions {
+        layout: args.layout,
+        dynamic_layout_params,
+        proof_mode,
+        max_steps: args.max_steps,
+        pie_resources_check: if args.warn_inconsistent_pies {
+            PieResourcesCheck::WarnOnly
+        } else {
+            PieResourcesCheck::default()
+        },
+        ..Default::default()
+    }
+}
 
-    std::fs::create_dir_all(&args.output_path).unwrap();
-    let (private_input, public_input) = prover_input_from_runner(&runner, &args.output_path);
+fn print_pie_resources_warnings(hint_processor: &BootloaderHintProcessor) {
+    for report in hint_processor.pie_resources_reports() {
+        if !report.is_consistent() {
+            eprintln!("warning: {report}");
+        }
+    }
+}
 
-    let priv_json = serde_json::to_string(&private_input).unwrap();
-    let pub_json = serde_json::to_string(&public_input).unwrap();
-    std::fs::write(args.output_path.join("priv.json"), priv_json).unwrap();
-    std::fs::write(args.output_path.join("pub.json"), pub_json).unwrap();
+/// Runs the bootloader with the hint processor configured by `options`.
+fn run_with_hint_processor(
+    args: &RunArgs,
+    bootloader_program: &Program,
+    tasks: Vec<TaskSpec>,
+    options: &RunOptions,
+) -> Result<CairoRunner, Box<dyn Error>> {
+    let mut hint_processor = options.hint_processor();
+    check_task_hints(&tasks, &hint_processor)?;
+    let run = |hint_processor: &mut dyn HintProcessor| {
+        run_bootloader_with_hint_processor(bootloader_program, tasks, options, hint_processor)
+    };
+    let runner = if args.profile_hints {
+        let mut profiler = HintProfiler::new(hint_processor);
+        let runner = run(&mut profiler);
+        // Also useful when the run fails, e.g. to find the hint a slow run got stuck in.
+        println!("{}", profiler.profile());
+        hint_processor = profiler.into_inner();
+        runner
+    } else {
+        run(&mut hint_processor)
+    };
+    let runner = runner.map_err(|e| cairo_bootloader::Error::from_run_error(e, &hint_processor))?;
+    print_pie_resources_warnings(&hint_processor);
+    Ok(runner)
+}
 
+fn print_execution_resources(runner: &CairoRunner) {
     let resources = runner
         .get_execution_resources()
         .expect("failed to get execution resources, but the run was successful");
@@ -195,5 +308,367 @@ fn main() -> Result<(), Box<dyn Error>> {
     println!("n_memory_holes: {}", resources.n_memory_holes);
     println!("builtin_instance_counter: {:#?}", builtin_instance_counter);
 
+    match CapacityReport::from_runner(runner) {
+        Ok(capacity) => println!("{capacity}"),
+        Err(e) => eprintln!("warning: failed to compute the capacity report: {e}"),
+    }
+}
+
+fn print_run_summary(runner: &mut CairoRunner) -> Result<(), Box<dyn Error>> {
+    let mut output_buffer = "Program Output:\n".to_string();
+    runner.vm.write_output(&mut output_buffer)?;
+    print!("{output_buffer}");
+    println!("--------------------------------");
+
+    print_execution_resources(runner);
+
+    Ok(())
+}
+
+fn make_run_report(args: &RunArgs, runner: &mut CairoRunner) -> Result<RunReport, Box<dyn Error>> {
+    let output = get_bootloader_output(runner)?;
+    let tasks = decode_task_outputs(&output)?
+        .into_iter()
+        .enumerate()
+        .map(|(task_index, task_output)| TaskReport {
+            pie: args.pie.get(task_index).cloned(),
+            program_hash: task_output.program_hash,
+            output: task_output.output,
+        })
+        .collect();
+    let resources = runner.get_execution_resources()?;
+
+    Ok(RunReport {
+        version: env!("CARGO_PKG_VERSION"),
+        layout: args.layout.to_str(),
+        tasks,
+        n_steps: resources.n_steps,
+        n_memory_holes: resources.n_memory_holes,
+        builtin_instance_counter: resources
+            .builtin_instance_counter
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect(),
+        capacity: CapacityReport::from_runner(runner)?,
+        artifacts: ArtifactsReport::default(),
+    })
+}
+
+fn write_run_report(path: &Path, report: &RunReport) -> Result<(), Box<dyn Error>> {
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(std::io::BufWriter::new(file), report)?;
+    Ok(())
+}
+
+fn load_tasks(args: &RunArgs) -> Result<Vec<TaskSpec>, Box<dyn Error>> {
+    let pie_paths: Vec<&Path> = args.pie.iter().map(|p| p.as_ref()).collect();
+    let max_steps: Vec<Option<usize>> = match args.max_task_steps.as_slice() {
+        [] => vec![None; pie_paths.len()],
+        [max_steps] => vec![Some(*max_steps); pie_paths.len()],
+        max_steps if max_steps.len() == pie_paths.len() => {
+            max_steps.iter().copied().map(Some).collect()
+        }
+        max_steps => {
+            return Err(format!(
+                "--max-task-steps takes one limit or one per PIE, got {} for {} PIEs",
+                max_steps.len(),
+                pie_paths.len()
+            )
+            .into())
+        }
+    };
+    Ok(make_bootloader_tasks(
+        None,
+        None,
+        Some(&pie_paths),
+        Some(&max_steps),
+    )?)
+}
+
+fn execute(args: &RunArgs) -> Result<(), Box<dyn Error>> {
+    let bootloader_program = load_bootloader()?;
+    let dynamic_layout_params = load_dynamic_layout_params(args)?;
+    check_layout_builtins(
+        &bootloader_program,
+        args.layout,
+        dynamic_layout_params.clone(),
+    )?;
+    let tasks = load_tasks(args)?;
+
+    let options = make_run_options(args, false, dynamic_layout_params);
+    let mut runner = run_with_hint_processor(args, &bootloader_program, tasks, &options)?;
+
+    let output = get_bootloader_output(&mut runner)?;
+    for (task_index, task_output) in decode_task_outputs(&output)?.into_iter().enumerate() {
+        let output: Vec<String> = task_output
+            .output
+            .iter()
+            .map(|felt| felt.to_string())
+            .collect();
+        println!(
+            "Task {task_index} (program hash {:#x}): [{}]",
+            task_output.program_hash,
+            output.join(", ")
+        );
+    }
+    println!("--------------------------------");
+
+    print_execution_resources(&runner);
+
+    if let Some(report_path) = &args.report {
+        let report = make_run_report(args, &mut runner)?;
+        write_run_report(report_path, &report)?;
+    }
+
+    Ok(())
+}
+
+fn run_and_write_prover_input(
+    args: &ProveArgs,
+    bootloader_program: &Program,
+    tasks: Vec<TaskSpec>,
+    dynamic_layout_params: Option<CairoLayoutParams>,
+    output_path: &Path,
+) -> Result<(CairoRunner, ProverInputPaths), Box<dyn Error>> {
+    let options = make_run_options(&args.run, true, dynamic_layout_params);
+    let mut runner = run_with_hint_processor(&args.run, bootloader_program, tasks, &options)?;
+    print_run_summary(&mut runner)?;
+
+    let mut file_names = ProverInputFileNames::default();
+    if args.air_private_input {
+        file_names = file_names.with_air_private_input();
+    }
+    let prover_input_paths = write_prover_input(&runner, output_path, &file_names)?;
+
+    Ok((runner, prover_input_paths))
+}
+
+fn prove_in_batches(
+    args: &ProveArgs,
+    bootloader_program: &Program,
+    tasks: Vec<TaskSpec>,
+    dynamic_layout_params: Option<CairoLayoutParams>,
+    output_path: &Path,
+    budget: BatchBudget,
+) -> Result<(), Box<dyn Error>> {
+    let options = make_run_options(&args.run, true, dynamic_layout_params.clone());
+    let plan = BatchPlanner::measure(budget, &options)?.plan(&tasks)?;
+    println!(
+        "Splitting {} tasks into {} bootloader runs",
+        tasks.len(),
+        plan.runs.len()
+    );
+
+    let mut index = BatchIndex { runs: vec![] };
+    for (run_index, run) in plan.runs.iter().enumerate() {
+        let run_tasks = run
+            .task_indices
+            .iter()
+            .map(|task_index| tasks[*task_index].clone())
+            .collect();
+        let directory = output_path.join(format!("run_{run_index}"));
+        println!("Run {run_index}: tasks {:?}", run.task_indices);
+        let (runner, _) = run_and_write_prover_input(
+            args,
+            bootloader_program,
+            run_tasks,
+            dynamic_layout_params.clone(),
+            &directory,
+        )?;
+
+        index.runs.push(BatchIndexRun {
+            directory,
+            estimated_steps: run.estimated.n_steps,
+            n_steps: runner.get_execution_resources()?.n_steps,
+            tasks: run
+                .task_indices
+                .iter()
+                .map(|task_index| BatchIndexTask {
+                    index: *task_index,
+                    pie: args.run.pie[*task_index].clone(),
+                })
+                .collect(),
+        });
+    }
+
+    let index_file = File::create(output_path.join("batch_index.json"))?;
+    serde_json::to_writer_pretty(std::io::BufWriter::new(index_file), &index)?;
+
+    Ok(())
+}
+
+fn inspect(args: &InspectArgs) -> Result<(), Box<dyn Error>> {
+    let cairo_pie = CairoPie::read_zip_file(&args.pie)?;
+    let metadata = &cairo_pie.metadata;
+    let program = &metadata.program;
+
+    println!("Program:");
+    println!("  size: {}", program.data.len());
+    println!("  main: {}", program.main);
+    let builtins: Vec<&str> = program.builtins.iter().map(|b| b.to_str()).collect();
+    println!("  builtins: [{}]", builtins.join(", "));
+    println!(
+        "  hash chain: {:#x}",
+        compute_program_hash_chain(program, 0)?
+    );
+
+    let mut segments = vec![
+        ("program", &metadata.program_segment),
+        ("execution", &metadata.execution_segment),
+        ("ret_fp", &metadata.ret_fp_segment),
+        ("ret_pc", &metadata.ret_pc_segment),
+    ];
+    segments.extend(
+        metadata
+            .builtin_segments
+            .iter()
+            .map(|(builtin, segment)| (builtin.to_str(), segment)),
+    );
+    segments.extend(
+        metadata
+            .extra_segments
+            .iter()
+            .map(|segment| ("extra", segment)),
+    );
+    segments.sort_by_key(|(_, segment)| segment.index);
+    println!("Segments:");
+    for (name, segment) in segments {
+        println!("  {}: {name} (size {})", segment.index, segment.size);
+    }
+
+    let resources = &cairo_pie.execution_resources;
+    let mut builtin_instance_counter: Vec<(&str, usize)> = resources
+        .builtin_instance_counter
+        .iter()
+        .map(|(builtin, count)| (builtin.to_str(), *count))
+        .collect();
+    builtin_instance_counter.sort();
+    println!("Execution resources:");
+    println!("  n_steps: {}", resources.n_steps);
+    println!("  n_memory_holes: {}", resources.n_memory_holes);
+    for (builtin, count) in builtin_instance_counter {
+        println!("  {builtin}: {count}");
+    }
+
+    if let Some(BuiltinAdditionalData::Output(output_data)) =
+        cairo_pie.additional_data.0.get(&BuiltinName::output)
+    {
+        let mut pages: Vec<_> = output_data.pages.iter().collect();
+        pages.sort_by_key(|(page_id, _)| **page_id);
+        let mut attributes: Vec<_> = output_data.attributes.iter().collect();
+        attributes.sort();
+        println!("Output builtin:");
+        for (page_id, page) in pages {
+            println!("  page {page_id}: start {}, size {}", page.start, page.size);
+        }
+        for (name, value) in attributes {
+            println!("  attribute {name}: {value:?}");
+        }
+    }
+
+    let output_size = metadata
+        .builtin_segments
+        .get(&BuiltinName::output)
+        .map(|segment| segment.size)
+        .unwrap_or(0);
+    let task: Box<dyn Task> = Box::new(CairoPieTask::new(cairo_pie, false));
+    let fact_topology = get_task_fact_topology(
+        output_size,
+        &task,
+        &mut OutputBuiltinRunner::new(true),
+        None,
+    )?;
+    println!("Fact topology:");
+    println!("  tree_structure: {:?}", fact_topology.tree_structure);
+    println!("  page_sizes: {:?}", fact_topology.page_sizes);
+
+    Ok(())
+}
+
+fn prove(args: &ProveArgs) -> Result<(), Box<dyn Error>> {
+    let bootloader_program = load_bootloader()?;
+    let dynamic_layout_params = load_dynamic_layout_params(&args.run)?;
+    check_layout_builtins(
+        &bootloader_program,
+        args.run.layout,
+        dynamic_layout_params.clone(),
+    )?;
+    let tasks = load_tasks(&args.run)?;
+
+    // When both runs happen, the report describes the proof mode run.
+    let mut report = None;
+    let mut artifacts = ArtifactsReport::default();
+
+    if let Some(cairo_pie_output) = &args.cairo_pie_output {
+        let options = make_run_options(&args.run, false, dynamic_layout_params.clone());
+        let mut runner =
+            run_with_hint_processor(&args.run, &bootloader_program, tasks.clone(), &options)?;
+        print_run_summary(&mut runner)?;
+
+        if let Some(parent) = cairo_pie_output.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        runner
+            .get_cairo_pie()?
+            .write_zip_file(cairo_pie_output, false)?;
+
+        if args.run.report.is_some() {
+            report = Some(make_run_report(&args.run, &mut runner)?);
+        }
+        artifacts.cairo_pie = Some(cairo_pie_output.clone());
+    }
+
+    let budget = BatchBudget {
+        max_steps: args.max_steps_per_run,
+        max_trace_log_size: args.max_trace_log_size_per_run,
+    };
+    let split_output_path = args
+        .output_path
+        .as_ref()
+        .filter(|_| budget != BatchBudget::default());
+    if let Some(output_path) = split_output_path {
+        return prove_in_batches(
+            args,
+            &bootloader_program,
+            tasks,
+            dynamic_layout_params,
+            output_path,
+            budget,
+        );
+    }
+
+    if let Some(output_path) = &args.output_path {
+        let (mut runner, prover_input_paths) = run_and_write_prover_input(
+            args,
+            &bootloader_program,
+            tasks,
+            dynamic_layout_params,
+            output_path,
+        )?;
+
+        if args.run.report.is_some() {
+            report = Some(make_run_report(&args.run, &mut runner)?);
+        }
+        artifacts.trace = Some(prover_input_paths.trace);
+        artifacts.memory = Some(prover_input_paths.memory);
+        artifacts.private_input = Some(prover_input_paths.private_input);
+        artifacts.public_input = Some(prover_input_paths.public_input);
+        artifacts.air_private_input = prover_input_paths.air_private_input;
+    }
+
+    if let (Some(report_path), Some(mut report)) = (&args.run.report, report) {
+        report.artifacts = artifacts;
+        write_run_report(report_path, &report)?;
+    }
+
     Ok(())
 }
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    match &cli.command {
+        Some(Command::Execute(args)) => execute(args),
+        Some(Command::Inspect(args)) => inspect(args),
+        None => prove(&cli.prove),
+    }
+}
@@ -0,0 +1,275 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use bincode::enc::write::Writer;
+use cairo_vm::air_public_input::{PublicInput, PublicInputError};
+use cairo_vm::cairo_run::{write_encoded_memory, write_encoded_trace, EncodeTraceError};
+use cairo_vm::vm::runners::cairo_runner::CairoRunner;
+use serde::{Deserialize, Serialize};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ProverInputError {
+    #[error("The run has no relocated trace, it must be run with trace_enabled and relocate_mem")]
+    TraceNotRelocated,
+
+    #[error("Failed to get the AIR public input: {0}")]
+    PublicInput(#[from] PublicInputError),
+
+    #[error("Failed to encode the trace or memory: {0}")]
+    Encoding(#[from] EncodeTraceError),
+
+    #[error("Failed to serialize the prover input: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Failed to write {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl ProverInputError {
+    fn io(path: &Path) -> impl FnOnce(std::io::Error) -> Self + '_ {
+        move |source| Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivateInput {
+    pub trace_path: PathBuf,
+    pub memory_path: PathBuf,
+}
+
+/// Names of the files written in the output directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProverInputFileNames {
+    pub trace: String,
+    pub memory: String,
+    pub private_input: String,
+    pub public_input: String,
+    /// Builtin private inputs in the `air_private_input.json` format, only written if set.
+    pub air_private_input: Option<String>,
+}
+
+impl ProverInputFileNames {
+    /// Also writes the builtin private inputs, as expected by provers other than `adapted_stwo`.
+    pub fn with_air_private_input(mut self) -> Self {
+        self.air_private_input = Some("air_private_input.json".to_string());
+        self
+    }
+}
+
+impl Default for ProverInputFileNames {
+    fn default() -> Self {
+        Self {
+            trace: "trace".to_string(),
+            memory: "memory".to_string(),
+            private_input: "priv.json".to_string(),
+            public_input: "pub.json".to_string(),
+            air_private_input: None,
+        }
+    }
+}
+
+/// Paths of the files written by `write_prover_input`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProverInputPaths {
+    pub trace: PathBuf,
+    pub memory: PathBuf,
+    pub private_input: PathBuf,
+    pub public_input: PathBuf,
+    pub air_private_input: Option<PathBuf>,
+}
+
+pub struct FileWriter {
+    buf_writer: BufWriter<File>,
+    bytes_written: usize,
+}
+
+impl Writer for FileWriter {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), bincode::error::EncodeError> {
+        self.buf_writer
+            .write_all(bytes)
+            .map_err(|e| bincode::error::EncodeError::Io {
+                inner: e,
+                index: self.bytes_written,
+            })?;
+
+        self.bytes_written += bytes.len();
+
+        Ok(())
+    }
+}
+
+impl FileWriter {
+    pub fn new(buf_writer: BufWriter<File>) -> Self {
+        Self {
+            buf_writer,
+            bytes_written: 0,
+        }
+    }
+
+    /// Flushes the buffered bytes and returns the underlying file.
+    pub fn into_inner(self) -> std::io::Result<File> {
+        self.buf_writer.into_inner().map_err(|e| e.into_error())
+    }
+}
+
+/// Writes a file by writing a temporary file in the same directory first, then renaming it.
+///
+/// Readers of `path` never observe a partially written file, and a failed write leaves no file
+/// behind.
+fn write_atomically<F>(
+    path: &Path,
+    buffer_capacity: usize,
+    write: F,
+) -> Result<(), ProverInputError>
+where
+    F: FnOnce(&mut FileWriter) -> Result<(), ProverInputError>,
+{
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = path.with_file_name(format!(".{file_name}.tmp"));
+
+    let result = File::create(&tmp_path)
+        .map_err(ProverInputError::io(&tmp_path))
+        .and_then(|file| {
+            let mut writer = FileWriter::new(BufWriter::with_capacity(buffer_capacity, file));
+            write(&mut writer)?;
+            let file = writer
+                .into_inner()
+                .map_err(ProverInputError::io(&tmp_path))?;
+            file.sync_all().map_err(ProverInputError::io(&tmp_path))
+        })
+        .and_then(|_| std::fs::rename(&tmp_path, path).map_err(ProverInputError::io(path)));
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+fn write_json_atomically<T: Serialize>(path: &Path, value: &T) -> Result<(), ProverInputError> {
+    write_atomically(path, 1024 * 1024, |writer| {
+        Ok(serde_json::to_writer(&mut writer.buf_writer, value)?)
+    })
+}
+
+/// Writes the trace and memory files of a proof mode run in `output_dir` and returns the
+/// private and public inputs of the prover.
+pub fn prover_input_from_runner<'r>(
+    runner: &'r CairoRunner,
+    output_dir: &Path,
+    file_names: &ProverInputFileNames,
+) -> Result<(PrivateInput, PublicInput<'r>), ProverInputError> {
+    let public_input = runner.get_air_public_input()?;
+    // Encode straight from the runner buffers, the trace of a large run takes gigabytes.
+    let trace = runner
+        .relocated_trace
+        .as_ref()
+        .ok_or(ProverInputError::TraceNotRelocated)?;
+
+    let trace_path = output_dir.join(&file_names.trace);
+    write_atomically(&trace_path, 3 * 1024 * 1024, |writer| {
+        Ok(write_encoded_trace(trace, writer)?)
+    })?;
+
+    let memory_path = output_dir.join(&file_names.memory);
+    write_atomically(&memory_path, 5 * 1024 * 1024, |writer| {
+        Ok(write_encoded_memory(&runner.relocated_memory, writer)?)
+    })?;
+
+    let private_input = PrivateInput {
+        trace_path: std::fs::canonicalize(&trace_path)
+            .map_err(ProverInputError::io(&trace_path))?,
+        memory_path: std::fs::canonicalize(&memory_path)
+            .map_err(ProverInputError::io(&memory_path))?,
+    };
+    Ok((private_input, public_input))
+}
+
+/// Writes all the stwo prover input files of a proof mode run in `output_dir`, creating it if
+/// needed.
+pub fn write_prover_input(
+    runner: &CairoRunner,
+    output_dir: &Path,
+    file_names: &ProverInputFileNames,
+) -> Result<ProverInputPaths, ProverInputError> {
+    std::fs::create_dir_all(output_dir).map_err(ProverInputError::io(output_dir))?;
+    let (private_input, public_input) = prover_input_from_runner(runner, output_dir, file_names)?;
+
+    let private_input_path = output_dir.join(&file_names.private_input);
+    write_json_atomically(&private_input_path, &private_input)?;
+    let public_input_path = output_dir.join(&file_names.public_input);
+    write_json_atomically(&public_input_path, &public_input)?;
+
+    let air_private_input_path = match &file_names.air_private_input {
+        Some(file_name) => {
+            let path = output_dir.join(file_name);
+            let air_private_input = runner.get_air_private_input().to_serializable(
+                private_input.trace_path.to_string_lossy().into_owned(),
+                private_input.memory_path.to_string_lossy().into_owned(),
+            );
+            write_json_atomically(&path, &air_private_input)?;
+            Some(path)
+        }
+        None => None,
+    };
+
+    Ok(ProverInputPaths {
+        trace: private_input.trace_path,
+        memory: private_input.memory_path,
+        private_input: private_input_path,
+        public_input: public_input_path,
+        air_private_input: air_private_input_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn make_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "cairo-bootloader-prover-input-{name}-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_write_json_atomically() {
+        let dir = make_test_dir("json");
+        let path = dir.join("priv.json");
+        let private_input = PrivateInput {
+            trace_path: PathBuf::from("trace"),
+            memory_path: PathBuf::from("memory"),
+        };
+
+        write_json_atomically(&path, &private_input).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, serde_json::to_string(&private_input).unwrap());
+        assert!(!dir.join(".priv.json.tmp").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_atomically_failure_leaves_no_file() {
+        let dir = make_test_dir("failure");
+        let path = dir.join("trace");
+
+        let result = write_atomically(&path, 16, |_| Err(ProverInputError::TraceNotRelocated));
+
+        assert_matches!(result, Err(ProverInputError::TraceNotRelocated));
+        assert!(!path.exists());
+        assert!(!dir.join(".trace.tmp").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,358 @@
+use std::path::PathBuf;
+
+use cairo_vm::cairo_run::{cairo_run_program_with_initial_scope, CairoRunConfig};
+use cairo_vm::hint_processor::hint_processor_definition::HintProcessor;
+use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::types::layout::CairoLayoutParams;
+use cairo_vm::types::layout_name::LayoutName;
+use cairo_vm::types::program::Program;
+use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
+use cairo_vm::vm::errors::memory_errors::MemoryError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+use cairo_vm::vm::runners::cairo_runner::{CairoRunner, ExecutionResources};
+use cairo_vm::Felt252;
+use num_traits::ToPrimitive;
+
+use crate::bootloaders::load_bootloader;
+use crate::{
+    check_task_hints, insert_bootloader_input, BootloaderConfig, BootloaderHintProcessor,
+    BootloaderInput, Error, FactTopology, PackedOutput, PieResourcesCheck, PieResourcesReport,
+    SimpleBootloaderInput, TaskSpec,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum BootloaderOutputError {
+    #[error(transparent)]
+    Vm(#[from] VirtualMachineError),
+
+    #[error(transparent)]
+    Memory(#[from] MemoryError),
+
+    #[error("Output segment size is not computed")]
+    SizeNotComputed,
+
+    #[error("Empty bootloader output")]
+    Empty,
+
+    #[error("Invalid number of tasks: {0}")]
+    InvalidNumberOfTasks(Felt252),
+
+    #[error("Invalid output header for task {0}")]
+    InvalidTaskHeader(usize),
+
+    #[error("{0} unexpected felts after the task outputs")]
+    TrailingFelts(usize),
+}
+
+/// Options of a bootloader run.
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    /// Layout used to run the bootloader.
+    pub layout: LayoutName,
+    /// Parameters of the dynamic layout, required with `LayoutName::dynamic`.
+    pub dynamic_layout_params: Option<CairoLayoutParams>,
+    /// Runs in proof mode, with the trace and relocated memory needed by the prover. Runs outside
+    /// of proof mode can be exported as a Cairo PIE.
+    pub proof_mode: bool,
+    /// Hash of the simple bootloader program, expected in the composite packed outputs.
+    pub simple_bootloader_program_hash: Felt252,
+    /// Hashes of the Cairo verifier programs accepted in the composite packed outputs.
+    pub supported_cairo_verifier_program_hashes: Vec<Felt252>,
+    /// Packed output of each task, plain outputs if `None`.
+    pub packed_outputs: Option<Vec<PackedOutput>>,
+    /// Writes the outputs of all the tasks in a single page.
+    pub single_page: bool,
+    /// Writes the fact topologies of the run to this JSON file.
+    pub fact_topologies_path: Option<PathBuf>,
+    /// Adds the outputs of the tasks to page 0 instead of one page per task.
+    pub ignore_fact_topologies: bool,
+    /// Limits the number of VM steps of the run, the limits of the tasks are set in their
+    /// `TaskSpec`.
+    pub max_steps: Option<usize>,
+    /// How to react when the resources recorded in a Cairo PIE task do not match the run.
+    pub pie_resources_check: PieResourcesCheck,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            layout: LayoutName::all_cairo_stwo,
+            dynamic_layout_params: None,
+            proof_mode: false,
+            simple_bootloader_program_hash: Felt252::ZERO,
+            supported_cairo_verifier_program_hashes: vec![],
+            packed_outputs: None,
+            single_page: false,
+            fact_topologies_path: None,
+            ignore_fact_topologies: true,
+            max_steps: None,
+            pie_resources_check: PieResourcesCheck::default(),
+        }
+    }
+}
+
+impl RunOptions {
+    pub fn bootloader_input(&self, tasks: Vec<TaskSpec>) -> BootloaderInput {
+        let packed_outputs = self
+            .packed_outputs
+            .clone()
+            .unwrap_or_else(|| vec![PackedOutput::Plain(vec![]); tasks.len()]);
+        BootloaderInput {
+            simple_bootloader_input: SimpleBootloaderInput {
+                fact_topologies_path: self.fact_topologies_path.clone(),
+                single_page: self.single_page,
+                tasks,
+            },
+            bootloader_config: BootloaderConfig {
+                simple_bootloader_program_hash: self.simple_bootloader_program_hash,
+                supported_cairo_verifier_program_hashes: self
+                    .supported_cairo_verifier_program_hashes
+                    .clone(),
+            },
+            packed_outputs,
+            ignore_fact_topologies: self.ignore_fact_topologies,
+        }
+    }
+
+    pub fn cairo_run_config(&self) -> CairoRunConfig<'static> {
+        CairoRunConfig {
+            entrypoint: "main",
+            trace_enabled: self.proof_mode,
+            relocate_mem: self.proof_mode,
+            layout: self.layout,
+            proof_mode: self.proof_mode,
+            secure_run: None,
+            disable_trace_padding: self.proof_mode,
+            allow_missing_builtins: None,
+            dynamic_layout_params: self.dynamic_layout_params.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub fn hint_processor(&self) -> BootloaderHintProcessor {
+        let mut hint_processor = BootloaderHintProcessor::new();
+        hint_processor.set_pie_resources_check(self.pie_resources_check);
+        hint_processor.set_max_steps(self.max_steps);
+        hint_processor
+    }
+}
+
+/// Output of a task, as written by the bootloader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskOutput {
+    pub program_hash: Felt252,
+    pub output: Vec<Felt252>,
+}
+
+/// A successful bootloader run.
+pub struct BootloaderRun {
+    pub runner: CairoRunner,
+    /// Output of each task, in order.
+    pub task_outputs: Vec<TaskOutput>,
+    /// Fact topologies of the task outputs, in order.
+    pub fact_topologies: Vec<FactTopology>,
+    pub resources: ExecutionResources,
+    /// Resource consistency reports of the Cairo PIE tasks.
+    pub pie_resources_reports: Vec<PieResourcesReport>,
+}
+
+/// Runs the bootloader on `tasks` with a custom hint processor. The options of the hint processor
+/// (`max_steps` and `pie_resources_check`) are not used.
+pub fn run_bootloader_with_hint_processor(
+    bootloader_program: &Program,
+    tasks: Vec<TaskSpec>,
+    options: &RunOptions,
+    hint_processor: &mut dyn HintProcessor,
+) -> Result<CairoRunner, CairoRunError> {
+    let mut exec_scopes = ExecutionScopes::new();
+    insert_bootloader_input(&mut exec_scopes, options.bootloader_input(tasks));
+
+    cairo_run_program_with_initial_scope(
+        bootloader_program,
+        &options.cairo_run_config(),
+        hint_processor,
+        exec_scopes,
+    )
+}
+
+/// Runs the bootloader on `tasks`. Fails with `Error::UnsupportedHints` before running if the
+/// program of a task uses hints that the bootloader hint processor does not implement.
+pub fn run_bootloader(tasks: Vec<TaskSpec>, options: &RunOptions) -> Result<BootloaderRun, Error> {
+    let bootloader_program = load_bootloader()?;
+    let mut hint_processor = options.hint_processor();
+    check_task_hints(&tasks, &hint_processor)?;
+    let mut runner = run_bootloader_with_hint_processor(
+        &bootloader_program,
+        tasks,
+        options,
+        &mut hint_processor,
+    )
+    .map_err(|e| Error::from_run_error(e, &hint_processor))?;
+
+    let output = get_bootloader_output(&mut runner)?;
+    let task_outputs = decode_task_outputs(&output)?;
+    let resources = runner.get_execution_resources()?;
+
+    Ok(BootloaderRun {
+        runner,
+        task_outputs,
+        fact_topologies: hint_processor.fact_topologies().to_vec(),
+        resources,
+        pie_resources_reports: hint_processor.pie_resources_reports().to_vec(),
+    })
+}
+
+/// Reads the output builtin segment of a run, which does not need to be relocated.
+pub fn get_bootloader_output(
+    runner: &mut CairoRunner,
+) -> Result<Vec<Felt252>, BootloaderOutputError> {
+    let output_base = runner.vm.get_output_builtin_mut()?.base();
+    let output_size = runner
+        .vm
+        .segments
+        .get_segment_used_size(output_base)
+        .ok_or(BootloaderOutputError::SizeNotComputed)?;
+    let output = runner
+        .vm
+        .get_integer_range((output_base as isize, 0).into(), output_size)?;
+    Ok(output.into_iter().map(|felt| felt.into_owned()).collect())
+}
+
+/// Splits the bootloader output, `n_tasks` followed by `(size, program_hash, *output)` for each
+/// task, into the program hash and output of each task.
+pub fn decode_task_outputs(output: &[Felt252]) -> Result<Vec<TaskOutput>, BootloaderOutputError> {
+    let (n_tasks, mut remaining) = output.split_first().ok_or(BootloaderOutputError::Empty)?;
+    let n_tasks = n_tasks
+        .to_usize()
+        .ok_or(BootloaderOutputError::InvalidNumberOfTasks(*n_tasks))?;
+
+    let mut task_outputs = Vec::with_capacity(n_tasks);
+    for task_index in 0..n_tasks {
+        let size = remaining
+            .first()
+            .and_then(|size| size.to_usize())
+            .filter(|size| (2..=remaining.len()).contains(size))
+            .ok_or(BootloaderOutputError::InvalidTaskHeader(task_index))?;
+        task_outputs.push(TaskOutput {
+            program_hash: remaining[1],
+            output: remaining[2..size].to_vec(),
+        });
+        remaining = &remaining[size..];
+    }
+
+    if !remaining.is_empty() {
+        return Err(BootloaderOutputError::TrailingFelts(remaining.len()));
+    }
+
+    Ok(task_outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use cairo_vm::vm::runners::cairo_pie::CairoPie;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::tasks::BootloaderTaskError;
+    use crate::{
+        CairoPieBytes, CairoPieTask, PieResourcesMismatch, RunProgramTask, UnsupportedHintsError,
+    };
+
+    const FIBONACCI_PIE: &[u8] = include_bytes!("../examples/fibonacci_with_output.zip");
+    const FIBONACCI_WITH_HINT: &str = include_str!("../examples/fibonacci_with_hint.json");
+
+    #[rstest]
+    #[case::empty(vec![], BootloaderOutputError::Empty)]
+    #[case::truncated(vec![2, 3, 0xabc, 5], BootloaderOutputError::InvalidTaskHeader(1))]
+    #[case::trailing_felts(vec![1, 2, 0xabc, 7], BootloaderOutputError::TrailingFelts(1))]
+    fn test_decode_invalid_task_outputs(
+        #[case] output: Vec<u64>,
+        #[case] expected: BootloaderOutputError,
+    ) {
+        let output: Vec<Felt252> = output.into_iter().map(Felt252::from).collect();
+        let error = decode_task_outputs(&output).unwrap_err();
+        assert_eq!(error.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_run_bootloader() {
+        let tasks = vec![TaskSpec::CairoPieBytes(CairoPieBytes::new(
+            FIBONACCI_PIE.to_vec(),
+            false,
+        ))];
+        let run = run_bootloader(tasks, &RunOptions::default()).expect("Bootloader run failed");
+
+        assert_matches!(
+            run.task_outputs.as_slice(),
+            [TaskOutput { output, .. }] if !output.is_empty()
+        );
+        assert_eq!(run.fact_topologies.len(), 1);
+        // The steps, memory holes and builtin segments observed match the ones of the PIE.
+        assert_matches!(
+            run.pie_resources_reports.as_slice(),
+            [report] if report.task_index == 0 && report.is_consistent()
+        );
+        assert!(run.resources.n_steps > 0);
+    }
+
+    #[rstest]
+    #[case::one_more_step(1)]
+    #[case::one_less_step(-1)]
+    fn test_run_bootloader_with_tampered_pie_steps(#[case] steps_delta: isize) {
+        let mut cairo_pie = CairoPie::from_bytes(FIBONACCI_PIE).unwrap();
+        let n_steps = cairo_pie.execution_resources.n_steps;
+        let tampered_n_steps = n_steps.checked_add_signed(steps_delta).unwrap();
+        cairo_pie.execution_resources.n_steps = tampered_n_steps;
+        let tasks = vec![TaskSpec::CairoPieTask(CairoPieTask::new(cairo_pie, false))];
+        let expected_mismatches = vec![PieResourcesMismatch::Steps {
+            recorded: tampered_n_steps,
+            observed: n_steps,
+        }];
+
+        let error = run_bootloader(tasks.clone(), &RunOptions::default()).unwrap_err();
+        assert_matches!(
+            error.root_cause(),
+            Error::PieResources(report) if report.mismatches == expected_mismatches
+        );
+
+        let options = RunOptions {
+            pie_resources_check: PieResourcesCheck::WarnOnly,
+            ..RunOptions::default()
+        };
+        let run = run_bootloader(tasks, &options).expect("Bootloader run failed");
+        assert_matches!(
+            run.pie_resources_reports.as_slice(),
+            [report] if report.mismatches == expected_mismatches
+        );
+    }
+
+    #[test]
+    fn test_run_bootloader_with_unsupported_hints() {
+        let program_json = FIBONACCI_WITH_HINT.replace(
+            "ids.fibonacci_claim_index = program_input['fibonacci_claim_index']",
+            "ids.fibonacci_claim_index = custom_claim_index()",
+        );
+        let program = Program::from_bytes(program_json.as_bytes(), Some("main")).unwrap();
+        let tasks = vec![TaskSpec::RunProgram(RunProgramTask::new(
+            program,
+            Default::default(),
+            false,
+        ))];
+
+        let error = run_bootloader(tasks, &RunOptions::default()).unwrap_err();
+        assert_matches!(error, Error::UnsupportedHints(UnsupportedHintsError(hints))
+            if hints.len() == 1 && hints[0].task_index == 0);
+    }
+
+    #[test]
+    fn test_run_bootloader_with_invalid_pie() {
+        let tasks = vec![TaskSpec::CairoPieBytes(CairoPieBytes::new(
+            b"not a zip file".to_vec(),
+            false,
+        ))];
+
+        let error = run_bootloader(tasks, &RunOptions::default()).unwrap_err();
+        assert_matches!(error.root_cause(), Error::Task(BootloaderTaskError::Pie(_)));
+    }
+}
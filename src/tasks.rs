@@ -14,10 +14,13 @@ pub enum BootloaderTaskError {
     Pie(#[from] std::io::Error),
 }
 
+/// Makes the tasks of `programs`, then of `pies`. `max_steps` holds the step limit of each task,
+/// in the same order (programs, then PIEs).
 pub fn make_bootloader_tasks(
     programs: Option<&[&Path]>,
     program_inputs: Option<&[HashMap<String, serde_json::Value>]>,
     pies: Option<&[&Path]>,
+    max_steps: Option<&[Option<usize>]>,
 ) -> Result<Vec<TaskSpec>, BootloaderTaskError> {
     let mut tasks: Vec<TaskSpec> = Vec::new();
     if let (Some(programs), Some(program_inputs)) = (programs, program_inputs) {
@@ -31,11 +34,11 @@ pub fn make_bootloader_tasks(
             |(program_file, program_input)| -> Result<(), BootloaderTaskError> {
                 let program = Program::from_file(program_file, Some("main"))
                     .map_err(BootloaderTaskError::Program)?;
-                tasks.push(TaskSpec::RunProgram(RunProgramTask {
+                tasks.push(TaskSpec::RunProgram(RunProgramTask::new(
                     program,
-                    program_input: program_input.clone(),
-                    use_poseidon: false,
-                }));
+                    program_input.clone(),
+                    false,
+                )));
                 Ok(())
             },
         )?;
@@ -45,13 +48,51 @@ pub fn make_bootloader_tasks(
         pies.iter()
             .try_for_each(|pie| -> Result<(), BootloaderTaskError> {
                 let cairo_pie = CairoPie::read_zip_file(pie).map_err(BootloaderTaskError::Pie)?;
-                tasks.push(TaskSpec::CairoPieTask(CairoPieTask {
-                    cairo_pie,
-                    use_poseidon: false,
-                }));
+                let mut task = CairoPieTask::new(cairo_pie, false);
+                task.source_path = Some(pie.to_path_buf());
+                tasks.push(TaskSpec::CairoPieTask(task));
                 Ok(())
             })?;
     }
 
+    if let Some(max_steps) = max_steps {
+        assert_eq!(
+            tasks.len(),
+            max_steps.len(),
+            "The length of max_steps must be the number of tasks"
+        );
+        for (task, max_steps) in tasks.iter_mut().zip(max_steps) {
+            task.set_max_steps(*max_steps);
+        }
+    }
+
     Ok(tasks)
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_make_bootloader_tasks_with_max_steps() {
+        let pie = Path::new("examples/fibonacci_with_output.zip");
+        let tasks = make_bootloader_tasks(None, None, Some(&[pie, pie]), Some(&[Some(100), None]))
+            .expect("Failed to make tasks");
+
+        assert_matches!(
+            tasks.as_slice(),
+            [
+                TaskSpec::CairoPieTask(CairoPieTask {
+                    max_steps: Some(100),
+                    ..
+                }),
+                TaskSpec::CairoPieTask(CairoPieTask {
+                    max_steps: None,
+                    ..
+                }),
+            ]
+        );
+    }
+}